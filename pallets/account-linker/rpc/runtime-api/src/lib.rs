@@ -0,0 +1,62 @@
+//! Runtime API definition for the account linker pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_account_linker::{
+	Eip712Domain, IdentityLink, LinkProof, LinkedIdentity, PendingPolkadotLink,
+	PolkadotIdentityLink,
+};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait AccountLinkerApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// The link nonce `account` has to sign in its next `link_eth`, `link_eth_eip712`,
+		/// `link_eth_contract`, `link_btc`, `link_tron`, `link_solana`, `link_cosmos` or
+		/// `link_polkadot_signed` request.
+		fn link_nonce(account: AccountId) -> u32;
+
+		/// The exact message to sign with an Ethereum, Tron, smart contract or Polkadot wallet
+		/// for a `link_eth`, `link_tron`, `link_eth_contract` or `link_polkadot_signed` request of
+		/// `account`, before the prefix the wallet adds itself.
+		fn link_message(
+			account: AccountId,
+			expiring_block_number: BlockNumber,
+			nonce: u32,
+		) -> Vec<u8>;
+
+		/// The exact text message to sign with a Bitcoin, Solana or Cosmos wallet for a
+		/// `link_btc`, `link_solana` or `link_cosmos` request of `account`.
+		fn link_text_message(
+			account: AccountId,
+			expiring_block_number: BlockNumber,
+			nonce: u32,
+		) -> Vec<u8>;
+
+		/// The EIP-712 domain of the typed data to sign with an Ethereum wallet for a
+		/// `link_eth_eip712` request. Its salt is the genesis hash of the chain.
+		fn eip712_domain() -> Eip712Domain;
+
+		/// The EIP-712 `hashStruct` of the `Link` message of a `link_eth_eip712` request of
+		/// `account`.
+		fn eip712_link_struct_hash(
+			account: AccountId,
+			expiring_block_number: BlockNumber,
+			nonce: u32,
+		) -> [u8; 32];
+
+		/// The EIP-712 typed data hash signed for a `link_eth_eip712` request of `account`.
+		fn eip712_link_hash(
+			account: AccountId,
+			expiring_block_number: BlockNumber,
+			nonce: u32,
+		) -> [u8; 32];
+
+		/// All the addresses linked to `account` in their display encodings, the block each was
+		/// linked at and the pending `link_polkadot` requests of `account`.
+		fn linked_identity(account: AccountId) -> LinkedIdentity<AccountId, BlockNumber>;
+	}
+}
@@ -0,0 +1,182 @@
+//! RPC interface for the account linker pallet.
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+pub use pallet_account_linker_rpc_runtime_api::AccountLinkerApi as AccountLinkerRuntimeApi;
+use pallet_account_linker_rpc_runtime_api::{Eip712Domain, LinkedIdentity};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::sync::Arc;
+
+#[rpc]
+pub trait AccountLinkerApi<BlockHash, AccountId, BlockNumber> {
+	/// The link nonce `account` has to sign in its next link request
+	#[rpc(name = "accountLinker_linkNonce")]
+	fn link_nonce(&self, account: AccountId, at: Option<BlockHash>) -> Result<u32>;
+
+	/// The exact message to sign with an Ethereum, Tron, smart contract or Polkadot wallet
+	#[rpc(name = "accountLinker_linkMessage")]
+	fn link_message(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<BlockHash>,
+	) -> Result<Bytes>;
+
+	/// The exact text message to sign with a Bitcoin, Solana or Cosmos wallet
+	#[rpc(name = "accountLinker_linkTextMessage")]
+	fn link_text_message(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<BlockHash>,
+	) -> Result<Bytes>;
+
+	/// The EIP-712 domain of the typed data to sign with an Ethereum wallet
+	#[rpc(name = "accountLinker_eip712Domain")]
+	fn eip712_domain(&self, at: Option<BlockHash>) -> Result<Eip712Domain>;
+
+	/// The EIP-712 `hashStruct` of the `Link` message
+	#[rpc(name = "accountLinker_eip712LinkStructHash")]
+	fn eip712_link_struct_hash(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<BlockHash>,
+	) -> Result<H256>;
+
+	/// The EIP-712 typed data hash to sign with an Ethereum wallet
+	#[rpc(name = "accountLinker_eip712LinkHash")]
+	fn eip712_link_hash(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<BlockHash>,
+	) -> Result<H256>;
+
+	/// Everything linked to `account`, with the addresses in their display encodings
+	#[rpc(name = "accountLinker_linkedIdentity")]
+	fn linked_identity(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<LinkedIdentity<AccountId, BlockNumber>>;
+}
+
+/// Serves the `AccountLinkerApi` runtime API of the best block, or of the block `at`
+pub struct AccountLinker<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> AccountLinker<C, B> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+// Error code of a failed runtime API call
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query the account linker.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, BlockNumber>
+	AccountLinkerApi<<Block as BlockT>::Hash, AccountId, BlockNumber> for AccountLinker<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: AccountLinkerRuntimeApi<Block, AccountId, BlockNumber>,
+	AccountId: Codec,
+	BlockNumber: Codec,
+{
+	fn link_nonce(&self, account: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<u32> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.link_nonce(&at, account).map_err(runtime_error)
+	}
+
+	fn link_message(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Bytes> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.link_message(&at, account, expiring_block_number, nonce)
+			.map(Into::into)
+			.map_err(runtime_error)
+	}
+
+	fn link_text_message(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Bytes> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.link_text_message(&at, account, expiring_block_number, nonce)
+			.map(Into::into)
+			.map_err(runtime_error)
+	}
+
+	fn eip712_domain(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Eip712Domain> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.eip712_domain(&at).map_err(runtime_error)
+	}
+
+	fn eip712_link_struct_hash(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<H256> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.eip712_link_struct_hash(&at, account, expiring_block_number, nonce)
+			.map(Into::into)
+			.map_err(runtime_error)
+	}
+
+	fn eip712_link_hash(
+		&self,
+		account: AccountId,
+		expiring_block_number: BlockNumber,
+		nonce: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<H256> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.eip712_link_hash(&at, account, expiring_block_number, nonce)
+			.map(Into::into)
+			.map_err(runtime_error)
+	}
+
+	fn linked_identity(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<LinkedIdentity<AccountId, BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.linked_identity(&at, account).map_err(runtime_error)
+	}
+}
@@ -2,43 +2,420 @@
 
 use super::*;
 use crate::Pallet as AccountLinker;
+use btc::base58::ToBase58;
+use codec::Encode;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::{
+	traits::{Currency, Get, ReservableCurrency},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use libsecp256k1::{Message, PublicKey, SecretKey};
 
-use sp_std::prelude::*;
+use sp_runtime::traits::{Bounded, IdentifyAccount, Saturating};
+use sp_std::{convert::TryFrom, prelude::*};
 
 const SEED: u32 = 0;
 
+// The secp256k1 key used to sign link requests in benchmarks
+fn secret_key() -> SecretKey {
+	SecretKey::parse(&sp_io::hashing::keccak_256(b"Alice")).unwrap()
+}
+
+fn eth_address(secret: &SecretKey) -> EthAddress {
+	let pk = PublicKey::from_secret_key(secret).serialize();
+	let hashed_pk = sp_io::hashing::keccak_256(&pk[1..]);
+	let mut addr = [0u8; 20];
+	addr.copy_from_slice(&hashed_pk[12..]);
+	addr
+}
+
+// A slot linked at the current block
+fn link_record<T: Config, A>(address: A) -> LinkRecord<A, T::BlockNumber> {
+	let now = frame_system::Pallet::<T>::block_number();
+	LinkRecord {
+		address,
+		linked_at: now,
+		verified_at: now,
+		proof: LinkProof::PersonalSign,
+		label: None,
+	}
+}
+
+// rsv signature of a prehashed message
+fn sign(secret: &SecretKey, msg: &[u8; 32]) -> Signature {
+	let (sig, recovery_id) = libsecp256k1::sign(&Message::parse(msg), secret);
+	let mut rsv = [0u8; 65];
+	rsv[..64].copy_from_slice(&sig.serialize());
+	rsv[64] = recovery_id.serialize();
+	rsv
+}
+
+// BIP-137 signature of a Bitcoin signed message, for a compressed P2PKH address
+fn sign_btc(secret: &SecretKey, msg: &[u8]) -> Signature {
+	let rsv = sign(secret, &btc::message::signed_message_hash(msg));
+	let mut sig = [0u8; 65];
+	sig[0] = 31 + rsv[64];
+	sig[1..].copy_from_slice(&rsv[..64]);
+	sig
+}
+
+// Give `who` enough funds for any deposit
+fn fund<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+// The deposits of `slots` links inserted directly into storage, so that unlinking returns them
+fn hold_link_deposit<T: Config>(who: &T::AccountId, slots: u32) {
+	fund::<T>(who);
+	let deposit = T::LinkDeposit::get().saturating_mul(slots.into());
+	T::Currency::reserve(who, deposit).unwrap();
+	Deposits::<T>::insert(who, deposit);
+}
+
+// A fixed size address made from `i`, for the slots filled by benchmarks
+fn filler_address<A: Default + AsMut<[u8]>>(i: u32) -> A {
+	let mut addr = A::default();
+	addr.as_mut()[..4].copy_from_slice(&i.to_le_bytes());
+	addr
+}
+
+// All the slots of a linked address list, `last` at the last one
+fn full_links<T: Config, A, S: Get<u32>>(
+	last: A,
+	filler: impl Fn(u32) -> A,
+) -> BoundedVec<LinkRecord<A, T::BlockNumber>, S> {
+	let mut links: Vec<_> = (1..S::get()).map(|i| link_record::<T, _>(filler(i))).collect();
+	links.push(link_record::<T, _>(last));
+	BoundedVec::try_from(links).unwrap()
+}
+
+// The worst case of a link call replacing the last slot of `caller` with `addr`: all its slots
+// are taken and, under `LinkExclusivity::Transfer`, `addr` is transferred from `d` other accounts
+// with all their slots taken, so that `OnLinkChanged` is notified `d + 2` times. `insert` stores
+// the links of an account and their reverse index. Returns the mode of the link call.
+fn fill_slots<T: Config, A: Clone, S: Get<u32>>(
+	caller: &T::AccountId,
+	addr: A,
+	d: u32,
+	filler: impl Fn(u32) -> A,
+	insert: impl Fn(&T::AccountId, BoundedVec<LinkRecord<A, T::BlockNumber>, S>),
+) -> LinkMode {
+	let slots = S::get();
+	let links = |last: A| full_links::<T, A, S>(last, &filler);
+
+	insert(caller, links(filler(0)));
+	hold_link_deposit::<T>(caller, slots);
+	if T::LinkExclusivity::get() == LinkExclusivity::Transfer {
+		for i in 0..d {
+			let other: T::AccountId = account("other", i, SEED);
+			insert(&other, links(addr.clone()));
+			hold_link_deposit::<T>(&other, slots);
+		}
+	}
+	LinkMode::Replace(slots - 1)
+}
+
+// `fill_slots` for an Ethereum address, also used for the attestation that links a contract
+pub(crate) fn fill_eth_slots<T: Config>(
+	caller: &T::AccountId,
+	addr: EthAddress,
+	d: u32,
+) -> LinkMode {
+	fill_slots::<T, _, T::MaxEthLinks>(caller, addr, d, filler_address, |who, links| {
+		for link in links.iter() {
+			EthereumLinkAccounts::<T>::insert(&link.address, who, ());
+		}
+		EthereumLink::<T>::insert(who, links);
+	})
+}
+
+// The key type of the ed25519 key generated in the keystore to sign Solana link requests
+const SOLANA_KEY_TYPE: sp_core::crypto::KeyTypeId = sp_core::crypto::KeyTypeId(*b"sola");
+
+fn expiring_block_number<T: Config>() -> T::BlockNumber {
+	frame_system::Pallet::<T>::block_number() + 10000u32.into()
+}
+
 benchmarks! {
+	where_clause {
+		where
+			T::PolkadotSignature: From<sp_core::ecdsa::Signature>,
+			T::PolkadotSigner: From<sp_core::ecdsa::Public>,
+	}
+
 	link_eth {
-		let caller = account("Alice", 0, SEED);
-		let index: u32 = 0;
-		let addr_expected: EthAddress = [16, 146, 71, 235, 177, 95, 237, 92, 255, 45, 73, 190, 133, 132, 185, 41, 14, 77, 9, 207];
-		let expiring_block_number: u32 = 10000;
-		let sig: Signature = [133, 13, 66, 20, 141, 102, 233, 186, 153, 38, 81, 149, 29, 16, 191, 87, 206, 103, 230, 184, 32, 165, 174, 40, 221, 54, 212, 61, 132, 38, 254, 39, 19, 118, 77, 20, 241, 238, 52, 206, 124, 232, 254, 37, 109, 69, 191, 253, 242, 19, 48, 32, 92, 134, 123, 2, 6, 223, 233, 225, 129, 41, 235, 116, 28];
-	}:  link_eth(RawOrigin::Signed(caller), index, addr_expected, expiring_block_number.into(), sig)
+		let d in 0 .. T::MaxDisplacedAccounts::get();
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let addr_expected = eth_address(&secret);
+		let mode = fill_eth_slots::<T>(&caller, addr_expected, d);
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_raw_message(&caller, expiring_block_number, 0);
+		let sig = sign(&secret, &util_eth::eth_data_hash(bytes));
+	}:  link_eth(RawOrigin::Signed(caller), mode, addr_expected, expiring_block_number, 0, sig)
+
+	link_eth_eip712 {
+		let d in 0 .. T::MaxDisplacedAccounts::get();
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let addr_expected = eth_address(&secret);
+		let mode = fill_eth_slots::<T>(&caller, addr_expected, d);
+		let expiring_block_number = expiring_block_number::<T>();
+		let sig = sign(&secret, &AccountLinker::<T>::eip712_link_hash(&caller, expiring_block_number, 0));
+	}:  link_eth_eip712(RawOrigin::Signed(caller), mode, addr_expected, expiring_block_number, 0, sig)
+
+	link_eth_contract {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		fund::<T>(&caller);
+		let mode = LinkMode::Append;
+		let addr_expected: EthAddress = [7u8; 20];
+		let expiring_block_number = expiring_block_number::<T>();
+		let sig = vec![0u8; T::MaxContractSignatureLen::get() as usize];
+	}:  link_eth_contract(RawOrigin::Signed(caller), mode, addr_expected, expiring_block_number, 0, sig)
+
+	cancel_eth_contract_link {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		fund::<T>(&caller);
+		let addr: EthAddress = [7u8; 20];
+		let expiring_block_number = expiring_block_number::<T>();
+		let sig = vec![0u8; T::MaxContractSignatureLen::get() as usize];
+		crate::Pallet::<T>::link_eth_contract(RawOrigin::Signed(caller.clone()).into(), LinkMode::Append, addr, expiring_block_number, 0, sig)?;
+	}: _(RawOrigin::Signed(caller), addr)
 
 	link_btc {
-		let caller = account("caller", 0, 0);
-		let account_id: T::AccountId = account("Alice", 0, SEED);
-		let index: u32 = 0;
-		let addr_expected = vec![49, 51, 121, 55, 106, 72, 52, 85, 57, 113, 68, 112, 69, 77, 77, 119, 87, 90, 117, 52, 99, 122, 52, 107, 55, 67, 81, 107, 90, 72, 100, 101, 113, 71];
-		let expiring_block_number: u32 = 10000;
-		let sig: Signature = [250, 57, 156, 18, 181, 153, 186, 77, 81, 242, 31, 146, 82, 115, 85, 163, 136, 220, 104, 194, 98, 88, 28, 109, 163, 113, 12, 47, 193, 183, 189, 106, 41, 163, 172, 76, 129, 83, 66, 195, 126, 213, 207, 91, 186, 70, 255, 125, 111, 38, 123, 240, 178, 101, 22, 192, 133, 22, 245, 109, 50, 175, 225, 208, 0];
-	}:  link_btc(RawOrigin::Signed(caller), account_id.clone(), index, addr_expected, expiring_block_number.into(), sig)
+		let d in 0 .. T::MaxDisplacedAccounts::get();
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let pk = PublicKey::from_secret_key(&secret).serialize_compressed();
+		let addr_expected = btc::legacy::btc_addr_from_pk(&pk, T::BitcoinNetwork::get()).to_base58();
+		let mode = fill_slots::<T, _, T::MaxBtcLinks>(&caller, addr_expected.clone(), d, |i| i.to_le_bytes().to_vec(), |who, links| {
+			for link in links.iter() {
+				BitcoinLinkAccounts::<T>::insert(&link.address, who, ());
+			}
+			BitcoinLink::<T>::insert(who, links);
+		});
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_text_message(&caller, expiring_block_number, 0);
+		let sig = sign_btc(&secret, &bytes);
+	}:  link_btc(RawOrigin::Signed(caller), mode, addr_expected, expiring_block_number, 0, sig.to_vec())
+
+	link_tron {
+		let d in 0 .. T::MaxDisplacedAccounts::get();
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let addr_expected = eth_address(&secret);
+		let mode = fill_slots::<T, _, T::MaxTronLinks>(&caller, addr_expected, d, filler_address, |who, links| {
+			for link in links.iter() {
+				TronLinkAccounts::<T>::insert(&link.address, who, ());
+			}
+			TronLink::<T>::insert(who, links);
+		});
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_raw_message(&caller, expiring_block_number, 0);
+		let sig = sign(&secret, &util_eth::signed_data_hash(util_eth::TRON_MESSAGE_PREFIX, bytes));
+	}:  link_tron(RawOrigin::Signed(caller), mode, addr_expected, expiring_block_number, 0, sig)
+
+	link_solana {
+		let d in 0 .. T::MaxDisplacedAccounts::get();
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let public = sp_io::crypto::ed25519_generate(SOLANA_KEY_TYPE, None);
+		let mode = fill_slots::<T, _, T::MaxSolanaLinks>(&caller, public.0, d, filler_address, |who, links| {
+			for link in links.iter() {
+				SolanaLinkAccounts::<T>::insert(&link.address, who, ());
+			}
+			SolanaLink::<T>::insert(who, links);
+		});
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_text_message(&caller, expiring_block_number, 0);
+		let sig = sp_io::crypto::ed25519_sign(SOLANA_KEY_TYPE, &public, &bytes).unwrap();
+	}:  link_solana(RawOrigin::Signed(caller), mode, public.0, expiring_block_number, 0, sig.0)
+
+	link_cosmos {
+		let d in 0 .. T::MaxDisplacedAccounts::get();
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let pk = PublicKey::from_secret_key(&secret).serialize_compressed();
+		let addr_expected = cosmos::address_from_pk(T::CosmosHrp::get(), &pk).unwrap();
+		let mode = fill_slots::<T, _, T::MaxCosmosLinks>(&caller, addr_expected.clone(), d, |i| i.to_le_bytes().to_vec(), |who, links| {
+			for link in links.iter() {
+				CosmosLinkAccounts::<T>::insert(&link.address, who, ());
+			}
+			CosmosLink::<T>::insert(who, links);
+		});
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_text_message(&caller, expiring_block_number, 0);
+		let doc = cosmos::adr036_sign_doc(&addr_expected, &bytes);
+		let rsv = sign(&secret, &sp_io::hashing::sha2_256(&doc));
+		let mut sig = [0u8; 64];
+		sig.copy_from_slice(&rsv[..64]);
+	}:  link_cosmos(RawOrigin::Signed(caller), mode, addr_expected, expiring_block_number, 0, sig)
 
 	link_polkadot {
 		let caller = account("caller", 0, 0);
+		fund::<T>(&caller);
 		let linked_account: T::AccountId = account("Alice", 0, SEED);
-		let index: u32 = 0;
-	}: _(RawOrigin::Signed(caller), linked_account, index)
+		let mode = LinkMode::Append;
+		let expiring_block_number = expiring_block_number::<T>();
+	}: _(RawOrigin::Signed(caller), linked_account, mode, expiring_block_number)
 
 	accept_polkadot {
 		let caller: T::AccountId = account("caller", 0, 0);
+		fund::<T>(&caller);
 		let linked_account: T::AccountId = account("Alice", 0, SEED);
-		let index: u32 = 0;
-		crate::Pallet::<T>::link_polkadot(RawOrigin::Signed(caller.clone()).into(), linked_account.clone(), index)?;
+		let mode = LinkMode::Append;
+		let expiring_block_number = expiring_block_number::<T>();
+		crate::Pallet::<T>::link_polkadot(RawOrigin::Signed(caller.clone()).into(), linked_account.clone(), mode, expiring_block_number)?;
 	}: _(RawOrigin::Signed(linked_account), caller)
+
+	cancel_polkadot_link {
+		let caller: T::AccountId = account("caller", 0, 0);
+		fund::<T>(&caller);
+		let linked_account: T::AccountId = account("Alice", 0, SEED);
+		let mode = LinkMode::Append;
+		let expiring_block_number = expiring_block_number::<T>();
+		crate::Pallet::<T>::link_polkadot(RawOrigin::Signed(caller.clone()).into(), linked_account.clone(), mode, expiring_block_number)?;
+	}: _(RawOrigin::Signed(caller), linked_account)
+
+	link_polkadot_signed {
+		let caller: T::AccountId = account("caller", 0, 0);
+		fund::<T>(&caller);
+		let secret = secret_key();
+		let public = sp_core::ecdsa::Public::from_raw(PublicKey::from_secret_key(&secret).serialize_compressed());
+		let linked_account = T::PolkadotSigner::from(public).into_account();
+		let mode = LinkMode::Append;
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_raw_message(&caller, expiring_block_number, 0);
+		let sig = sp_core::ecdsa::Signature::from_raw(sign(&secret, &sp_io::hashing::blake2_256(&bytes)));
+	}: _(RawOrigin::Signed(caller), linked_account, mode, expiring_block_number, 0, sig.into())
+
+	unlink_eth {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let addr: EthAddress = [16, 146, 71, 235, 177, 95, 237, 92, 255, 45, 73, 190, 133, 132, 185, 41, 14, 77, 9, 207];
+		EthereumLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(addr)]).unwrap());
+		hold_link_deposit::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), addr)
+
+	unlink_btc {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let addr = vec![49, 51, 121, 55, 106, 72, 52, 85, 57, 113, 68, 112, 69, 77, 77, 119, 87, 90, 117, 52, 99, 122, 52, 107, 55, 67, 81, 107, 90, 72, 100, 101, 113, 71];
+		BitcoinLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(addr.clone())]).unwrap());
+		hold_link_deposit::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), addr)
+
+	unlink_tron {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let addr: EthAddress = [16, 146, 71, 235, 177, 95, 237, 92, 255, 45, 73, 190, 133, 132, 185, 41, 14, 77, 9, 207];
+		TronLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(addr)]).unwrap());
+		hold_link_deposit::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), addr)
+
+	unlink_solana {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let addr: SolanaAddress = [7u8; 32];
+		SolanaLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(addr)]).unwrap());
+		hold_link_deposit::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), addr)
+
+	unlink_cosmos {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let addr = b"cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c".to_vec();
+		CosmosLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(addr.clone())]).unwrap());
+		hold_link_deposit::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), addr)
+
+	unlink_polkadot {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let linked_account: T::AccountId = account("Alice", 0, SEED);
+		PolkadotLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(linked_account.clone())]).unwrap());
+		hold_link_deposit::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), linked_account)
+
+	set_link_label {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let addr: EthAddress = [16, 146, 71, 235, 177, 95, 237, 92, 255, 45, 73, 190, 133, 132, 185, 41, 14, 77, 9, 207];
+		EthereumLink::<T>::insert(&caller, BoundedVec::try_from(vec![link_record::<T, _>(addr)]).unwrap());
+		let label = vec![b'a'; T::MaxLinkLabelLen::get() as usize];
+	}: _(RawOrigin::Signed(caller), LinkedAddress::Ethereum(addr), Some(label))
+
+	refresh_link_eth {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let addr = eth_address(&secret);
+		EthereumLink::<T>::insert(&caller, full_links::<T, _, T::MaxEthLinks>(addr, filler_address));
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_raw_message(&caller, expiring_block_number, 0);
+		let sig = sign(&secret, &util_eth::eth_data_hash(bytes));
+	}: refresh_link(RawOrigin::Signed(caller), LinkedAddress::Ethereum(addr), expiring_block_number, 0, sig.to_vec())
+
+	// BIP-322 verifies a Schnorr signature in the runtime, unlike the BIP-137 ones
+	refresh_link_btc {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = k256::schnorr::SigningKey::from_bytes(&sp_io::hashing::keccak_256(b"Alice")).unwrap();
+		let mut output_key = [0u8; 32];
+		output_key.copy_from_slice(&secret.verifying_key().to_bytes());
+		let wp = btc::witness::WitnessProgram { version: 1, program: output_key.to_vec() };
+		let addr = wp.to_address(T::BitcoinNetwork::get().hrp().to_vec()).unwrap();
+		BitcoinLink::<T>::insert(&caller, full_links::<T, _, T::MaxBtcLinks>(addr.clone(), |i| i.to_le_bytes().to_vec()));
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_text_message(&caller, expiring_block_number, 0);
+		let sighash = btc::bip322::signature_hash(&output_key, &bytes, btc::bip322::SIGHASH_DEFAULT);
+		let sig = secret.try_sign_prehashed(&sighash, &[0u8; 32]).unwrap();
+		// the witness stack holding the signature
+		let mut witness = vec![1, 64];
+		witness.extend_from_slice(sig.as_bytes());
+	}: refresh_link(RawOrigin::Signed(caller), LinkedAddress::Bitcoin(addr), expiring_block_number, 0, witness)
+
+	refresh_link_tron {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let addr = eth_address(&secret);
+		TronLink::<T>::insert(&caller, full_links::<T, _, T::MaxTronLinks>(addr, filler_address));
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_raw_message(&caller, expiring_block_number, 0);
+		let sig = sign(&secret, &util_eth::signed_data_hash(util_eth::TRON_MESSAGE_PREFIX, bytes));
+	}: refresh_link(RawOrigin::Signed(caller), LinkedAddress::Tron(addr), expiring_block_number, 0, sig.to_vec())
+
+	refresh_link_solana {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let public = sp_io::crypto::ed25519_generate(SOLANA_KEY_TYPE, None);
+		SolanaLink::<T>::insert(&caller, full_links::<T, _, T::MaxSolanaLinks>(public.0, filler_address));
+		let expiring_block_number = expiring_block_number::<T>();
+		let bytes = AccountLinker::<T>::generate_text_message(&caller, expiring_block_number, 0);
+		let sig = sp_io::crypto::ed25519_sign(SOLANA_KEY_TYPE, &public, &bytes).unwrap();
+	}: refresh_link(RawOrigin::Signed(caller), LinkedAddress::Solana(public.0), expiring_block_number, 0, sig.0.to_vec())
+
+	refresh_link_cosmos {
+		let caller: T::AccountId = account("Alice", 0, SEED);
+		let secret = secret_key();
+		let pk = PublicKey::from_secret_key(&secret).serialize_compressed();
+		let addr = cosmos::address_from_pk(T::CosmosHrp::get(), &pk).unwrap();
+		CosmosLink::<T>::insert(&caller, full_links::<T, _, T::MaxCosmosLinks>(addr.clone(), |i| i.to_le_bytes().to_vec()));
+		// a signature whose key is only recovered with the last recovery id tried
+		let mut expiring_block_number = expiring_block_number::<T>();
+		let rsv = loop {
+			let bytes = AccountLinker::<T>::generate_text_message(&caller, expiring_block_number, 0);
+			let rsv = sign(&secret, &sp_io::hashing::sha2_256(&cosmos::adr036_sign_doc(&addr, &bytes)));
+			if rsv[64] == 1 {
+				break rsv
+			}
+			expiring_block_number = expiring_block_number + 1u32.into();
+		};
+	}: refresh_link(RawOrigin::Signed(caller), LinkedAddress::Cosmos(addr), expiring_block_number, 0, rsv[..64].to_vec())
+
+	refresh_link_polkadot {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let secret = secret_key();
+		let public = sp_core::ecdsa::Public::from_raw(PublicKey::from_secret_key(&secret).serialize_compressed());
+		let linked_account = T::PolkadotSigner::from(public).into_account();
+		PolkadotLink::<T>::insert(&caller, full_links::<T, _, T::MaxPolkadotLinks>(linked_account.clone(), |i| account("filler", i, SEED)));
+		let expiring_block_number = expiring_block_number::<T>();
+		// the message wrapped in `<Bytes>`, the second one checked
+		let mut bytes = b"<Bytes>".to_vec();
+		bytes.append(&mut AccountLinker::<T>::generate_raw_message(&caller, expiring_block_number, 0));
+		bytes.extend_from_slice(b"</Bytes>");
+		let sig: T::PolkadotSignature = sp_core::ecdsa::Signature::from_raw(sign(&secret, &sp_io::hashing::blake2_256(&bytes))).into();
+	}: refresh_link(RawOrigin::Signed(caller), LinkedAddress::Polkadot(linked_account), expiring_block_number, 0, sig.encode())
 }
 
 impl_benchmark_test_suite!(AccountLinker, crate::mock::new_test_ext(), crate::mock::Test,);
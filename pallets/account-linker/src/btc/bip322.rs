@@ -0,0 +1,243 @@
+//! BIP-322 "simple" signature verification for Taproot (P2TR key path) addresses
+//! https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+use super::{legacy::dsha256, message::compact_size};
+use k256::schnorr::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use sp_std::{convert::TryFrom, prelude::*};
+
+const MESSAGE_TAG: &[u8] = b"BIP0322-signed-message";
+const TAP_SIGHASH_TAG: &[u8] = b"TapSighash";
+
+pub const SIGHASH_DEFAULT: u8 = 0x00;
+pub const SIGHASH_ALL: u8 = 0x01;
+
+/// BIP-340 tagged hash: sha256(sha256(tag) || sha256(tag) || msg)
+pub fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+	let tag_hash = Sha256::digest(tag);
+
+	let mut hasher = Sha256::new();
+	hasher.update(&tag_hash);
+	hasher.update(&tag_hash);
+	hasher.update(msg);
+
+	let mut ret = [0; 32];
+	ret.copy_from_slice(&hasher.finalize()[..]);
+	ret
+}
+
+pub fn message_hash(msg: &[u8]) -> [u8; 32] {
+	tagged_hash(MESSAGE_TAG, msg)
+}
+
+/// Verifies a BIP-322 simple signature of `msg` for the Taproot output key of a P2TR address
+///
+/// # Arguments
+/// * output_key: the x-only output key, i.e. the witness program of the address
+/// * msg: the signed message
+/// * witness: the consensus encoded witness stack of the `to_sign` transaction
+pub fn verify_simple_p2tr(
+	output_key: &[u8; 32],
+	msg: &[u8],
+	witness: &[u8],
+) -> Result<(), &'static str> {
+	let stack = parse_witness(witness)?;
+	if stack.len() != 1 {
+		return Err("UnsupportedWitness")
+	}
+
+	let sig = &stack[0];
+	let sighash_type = match sig.len() {
+		64 => SIGHASH_DEFAULT,
+		65 if sig[64] == SIGHASH_ALL => SIGHASH_ALL,
+		_ => return Err("InvalidSignature"),
+	};
+
+	let sighash = signature_hash(output_key, msg, sighash_type);
+
+	let key = VerifyingKey::from_bytes(output_key).map_err(|_| "InvalidPublicKey")?;
+	let sig = Signature::try_from(&sig[..64]).map_err(|_| "InvalidSignature")?;
+	key.verify_prehashed(&sighash, &sig).map_err(|_| "InvalidSignature")
+}
+
+/// Computes the BIP-341 key path signature hash of the BIP-322 `to_sign` transaction proving
+/// the ownership of the P2TR output key for `msg`
+pub fn signature_hash(output_key: &[u8; 32], msg: &[u8], sighash_type: u8) -> [u8; 32] {
+	let mut script_pubkey = vec![0x51, 0x20];
+	script_pubkey.extend_from_slice(output_key);
+
+	let to_spend = to_spend_txid(&message_hash(msg), &script_pubkey);
+	taproot_signature_hash(&to_spend, &script_pubkey, sighash_type)
+}
+
+// The virtual transaction committing to the message, its only output is spent by `to_sign`
+fn to_spend_txid(message_hash: &[u8; 32], script_pubkey: &[u8]) -> [u8; 32] {
+	let mut tx = Vec::new();
+	// version
+	tx.extend_from_slice(&0u32.to_le_bytes());
+	// input: null prevout, scriptSig OP_0 PUSH32[message_hash], sequence 0
+	tx.push(1);
+	tx.extend_from_slice(&[0u8; 32]);
+	tx.extend_from_slice(&0xffff_ffffu32.to_le_bytes());
+	tx.push(34);
+	tx.push(0x00);
+	tx.push(0x20);
+	tx.extend_from_slice(message_hash);
+	tx.extend_from_slice(&0u32.to_le_bytes());
+	// output: value 0 paying to the address being proven
+	tx.push(1);
+	tx.extend_from_slice(&0u64.to_le_bytes());
+	tx.append(&mut compact_size(script_pubkey.len() as u64));
+	tx.extend_from_slice(script_pubkey);
+	// lock time
+	tx.extend_from_slice(&0u32.to_le_bytes());
+	dsha256(&tx)
+}
+
+// BIP-341 signature message of the `to_sign` transaction: version 0, lock time 0, a single
+// input spending `to_spend:0` with sequence 0 and a single OP_RETURN output of value 0
+fn taproot_signature_hash(
+	to_spend_txid: &[u8; 32],
+	script_pubkey: &[u8],
+	sighash_type: u8,
+) -> [u8; 32] {
+	let mut prevouts = to_spend_txid.to_vec();
+	prevouts.extend_from_slice(&0u32.to_le_bytes());
+
+	let mut script_pubkeys = compact_size(script_pubkey.len() as u64);
+	script_pubkeys.extend_from_slice(script_pubkey);
+
+	let mut outputs = 0u64.to_le_bytes().to_vec();
+	// OP_RETURN
+	outputs.extend_from_slice(&[0x01, 0x6a]);
+
+	// sighash epoch
+	let mut msg = vec![0x00];
+	msg.push(sighash_type);
+	// version and lock time
+	msg.extend_from_slice(&0u32.to_le_bytes());
+	msg.extend_from_slice(&0u32.to_le_bytes());
+	msg.extend_from_slice(&Sha256::digest(&prevouts));
+	// amounts
+	msg.extend_from_slice(&Sha256::digest(&0u64.to_le_bytes()));
+	msg.extend_from_slice(&Sha256::digest(&script_pubkeys));
+	// sequences
+	msg.extend_from_slice(&Sha256::digest(&0u32.to_le_bytes()));
+	msg.extend_from_slice(&Sha256::digest(&outputs));
+	// spend type: key path, no annex
+	msg.push(0x00);
+	// input index
+	msg.extend_from_slice(&0u32.to_le_bytes());
+
+	tagged_hash(TAP_SIGHASH_TAG, &msg)
+}
+
+fn parse_witness(witness: &[u8]) -> Result<Vec<Vec<u8>>, &'static str> {
+	let mut cursor = witness;
+	let count = read_compact_size(&mut cursor)?;
+
+	let mut stack = Vec::new();
+	for _ in 0..count {
+		let len = read_compact_size(&mut cursor)? as usize;
+		if cursor.len() < len {
+			return Err("InvalidWitness")
+		}
+		stack.push(cursor[..len].to_vec());
+		cursor = &cursor[len..];
+	}
+
+	if !cursor.is_empty() {
+		return Err("InvalidWitness")
+	}
+	Ok(stack)
+}
+
+fn read_compact_size(cursor: &mut &[u8]) -> Result<u64, &'static str> {
+	let (first, rest) = cursor.split_first().ok_or("InvalidWitness")?;
+	let size = match first {
+		0xfd => 2,
+		0xfe => 4,
+		0xff => 8,
+		n => {
+			*cursor = rest;
+			return Ok(*n as u64)
+		},
+	};
+	if rest.len() < size {
+		return Err("InvalidWitness")
+	}
+
+	let mut bytes = [0u8; 8];
+	bytes[..size].copy_from_slice(&rest[..size]);
+	*cursor = &rest[size..];
+	Ok(u64::from_le_bytes(bytes))
+}
+
+// test vectors from BIP-322, signed with the key L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::btc::witness::WitnessProgram;
+	use hex::decode;
+
+	const P2TR_ADDRESS: &[u8] = b"bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+	const P2TR_HELLO_WORLD_WITNESS: &str = "0141ddebd3eb25012ffa82937d9f25f9644e047bb2f472ab6c5089bbb53588ada2884cb5bcc53911f32d8dcf9548733b694d120db6a4e485194559e8d8fe668d269f01";
+
+	fn output_key() -> [u8; 32] {
+		let program = WitnessProgram::from_address(b"bc", P2TR_ADDRESS).unwrap().program;
+		let mut key = [0u8; 32];
+		key.copy_from_slice(&program);
+		key
+	}
+
+	#[test]
+	fn correct_message_hash() {
+		assert_eq!(
+			message_hash(b"")[..],
+			decode("c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1").unwrap()[..]
+		);
+		assert_eq!(
+			message_hash(b"Hello World")[..],
+			decode("f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a").unwrap()[..]
+		);
+	}
+
+	#[test]
+	fn correct_to_spend_txid() {
+		// P2WPKH scriptPubKey of bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l
+		let script_pubkey = decode("00142b05d564e6a7a33c087f16e0f730d1440123799d").unwrap();
+
+		let mut txid = to_spend_txid(&message_hash(b"Hello World"), &script_pubkey);
+		// txids are displayed in reversed byte order
+		txid.reverse();
+		assert_eq!(
+			txid[..],
+			decode("b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b").unwrap()[..]
+		);
+	}
+
+	#[test]
+	fn verify_p2tr_signature() {
+		let witness = decode(P2TR_HELLO_WORLD_WITNESS).unwrap();
+
+		assert!(verify_simple_p2tr(&output_key(), b"Hello World", &witness).is_ok());
+		assert!(verify_simple_p2tr(&output_key(), b"Hello World!", &witness).is_err());
+	}
+
+	#[test]
+	fn reject_malformed_witness() {
+		let witness = decode(P2TR_HELLO_WORLD_WITNESS).unwrap();
+
+		// trailing bytes
+		let mut long_witness = witness.clone();
+		long_witness.push(0);
+		assert!(verify_simple_p2tr(&output_key(), b"Hello World", &long_witness).is_err());
+
+		// truncated signature
+		assert!(verify_simple_p2tr(&output_key(), b"Hello World", &witness[..60]).is_err());
+
+		// explicit SIGHASH_DEFAULT is not a valid 65 bytes signature
+		let mut default_witness = witness.clone();
+		default_witness[66] = SIGHASH_DEFAULT;
+		assert!(verify_simple_p2tr(&output_key(), b"Hello World", &default_witness).is_err());
+	}
+}
@@ -1,12 +1,30 @@
+use super::BitcoinNetwork;
 use ripemd160::Ripemd160;
 use sha2::{Digest, Sha256};
 
-pub fn btc_addr_from_pk(pk: &[u8]) -> [u8; 25] {
-	let mut result = [0u8; 25];
+pub fn btc_addr_from_pk(pk: &[u8], network: BitcoinNetwork) -> [u8; 25] {
+	versioned_hash_with_checksum(network.p2pkh_version(), &hash160(pk))
+}
+
+/// Computes the P2SH-P2WPKH (nested segwit) address payload of a compressed public key
+///
+/// The redeem script is the P2WPKH witness program, i.e. OP_0 followed by a push of the
+/// keyhash (0x0014{20-byte keyhash}), and the address commits to its HASH160.
+/// https://github.com/bitcoin/bips/blob/master/bip-0049.mediawiki
+pub fn btc_p2sh_p2wpkh_addr_from_pk(pk: &[u8], network: BitcoinNetwork) -> [u8; 25] {
+	let mut redeem_script = [0u8; 22];
+	redeem_script[0] = 0;
+	redeem_script[1] = 20;
+	redeem_script[2..].copy_from_slice(&hash160(pk));
+
+	versioned_hash_with_checksum(network.p2sh_version(), &hash160(&redeem_script))
+}
 
-	// Now only support P2PKH (Mainnet) prefix = 0
-	result[0] = 0;
-	result[1..21].copy_from_slice(&hash160(pk));
+// version byte + hash + 4 bytes checksum, ready to be Base58 encoded
+pub fn versioned_hash_with_checksum(version: u8, hash: &[u8; 20]) -> [u8; 25] {
+	let mut result = [0u8; 25];
+	result[0] = version;
+	result[1..21].copy_from_slice(hash);
 	let cs = checksum(&result[0..21]);
 	result[21..25].copy_from_slice(&cs);
 	result
@@ -38,7 +56,7 @@ fn checksum(input: &[u8]) -> [u8; 4] {
 ///
 /// # Returns
 /// * The double SHA256 hash encoded as LE bytes from data
-fn dsha256(bytes: &[u8]) -> [u8; 32] {
+pub fn dsha256(bytes: &[u8]) -> [u8; 32] {
 	let mut hasher = Sha256::new();
 	hasher.update(bytes);
 	let digest = hasher.finalize();
@@ -55,6 +73,7 @@ fn dsha256(bytes: &[u8]) -> [u8; 32] {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::btc::base58::ToBase58;
 	use hex::decode;
 
 	#[test]
@@ -76,7 +95,7 @@ mod tests {
 		let mut pk_input = [0u8; 65];
 		pk_input[0..65].copy_from_slice(&pk[0..65]);
 
-		let addr = btc_addr_from_pk(&pk_input);
+		let addr = btc_addr_from_pk(&pk_input, BitcoinNetwork::Mainnet);
 
 		let addr_expected_hex =
 			decode("00010966776006953D5567439E5E39F86A0D273BEED61967F6").unwrap();
@@ -84,4 +103,41 @@ mod tests {
 		addr_expected[0..25].copy_from_slice(&addr_expected_hex[0..25]);
 		assert_eq!(addr, addr_expected);
 	}
+
+	#[test]
+	fn correct_btc_p2sh_p2wpkh_addr_from_pk() {
+		// BIP-49 test vector
+		let pk =
+			decode("03a1af804ac108a8a51782198c2d034b28bf90c8803f5a53f76276fa69a4eae77f").unwrap();
+
+		let addr = btc_p2sh_p2wpkh_addr_from_pk(&pk, BitcoinNetwork::Mainnet);
+
+		let addr_expected = decode("05336caa13e08b96080a32b5d818d59b4ab3b36742caacb161").unwrap();
+		assert_eq!(addr[..], addr_expected[..]);
+		assert_eq!(addr.to_base58(), b"36NvZTcMsMowbt78wPzJaHHWaNiyR73Y4g".to_vec());
+	}
+
+	#[test]
+	fn correct_testnet_btc_addr_from_pk() {
+		let pk = decode("0450863AD64A87AE8A2FE83C1AF1A8403CB53F53E486D8511DAD8A04887E5B23522CD470243453A299FA9E77237716103ABC11A1DF38855ED6F2EE187E9C582BA6").unwrap();
+
+		let addr_expected = decode("6f010966776006953d5567439e5e39f86a0d273bee85f8d86e").unwrap();
+		for network in &[BitcoinNetwork::Testnet, BitcoinNetwork::Signet, BitcoinNetwork::Regtest] {
+			let addr = btc_addr_from_pk(&pk, *network);
+			assert_eq!(addr[..], addr_expected[..]);
+			assert_eq!(addr.to_base58(), b"mfcSEPR8EkJrpX91YkTJ9iscdAzppJrG9j".to_vec());
+		}
+	}
+
+	#[test]
+	fn correct_testnet_btc_p2sh_p2wpkh_addr_from_pk() {
+		// BIP-49 test vector
+		let pk =
+			decode("03a1af804ac108a8a51782198c2d034b28bf90c8803f5a53f76276fa69a4eae77f").unwrap();
+
+		for network in &[BitcoinNetwork::Testnet, BitcoinNetwork::Signet, BitcoinNetwork::Regtest] {
+			let addr = btc_p2sh_p2wpkh_addr_from_pk(&pk, *network);
+			assert_eq!(addr.to_base58(), b"2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2".to_vec());
+		}
+	}
 }
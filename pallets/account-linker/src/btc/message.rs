@@ -0,0 +1,133 @@
+//! Bitcoin message signing as implemented by Bitcoin Core's `signmessage`, with the
+//! signature header extensions of BIP-137
+//! https://github.com/bitcoin/bips/blob/master/bip-0137.mediawiki
+use super::legacy::dsha256;
+use sp_std::prelude::*;
+
+pub const MESSAGE_PREFIX: &[u8] = b"\x18Bitcoin Signed Message:\n";
+
+/// The kind of address a BIP-137 signature claims to be produced for
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SignatureType {
+	P2PKHUncompressed,
+	P2PKHCompressed,
+	P2SHP2WPKH,
+	P2WPKH,
+}
+
+/// Computes the double SHA256 hash of a message in Bitcoin Signed Message format
+///
+/// # Arguments
+/// * msg: the message as it is shown to the user by the wallet
+///
+/// # Returns
+/// * The 32 bytes digest signed by the wallet
+pub fn signed_message_hash(msg: &[u8]) -> [u8; 32] {
+	let mut bytes = MESSAGE_PREFIX.to_vec();
+	bytes.append(&mut compact_size(msg.len() as u64));
+	bytes.extend_from_slice(msg);
+	dsha256(&bytes)
+}
+
+/// Splits a 65 bytes BIP-137 signature (header byte first) into its signature type and a
+/// 65 bytes rsv signature which can be passed to `secp256k1_ecdsa_recover`
+pub fn parse_signature(sig: &[u8; 65]) -> Result<(SignatureType, [u8; 65]), &'static str> {
+	let sig_type = match sig[0] {
+		27..=30 => SignatureType::P2PKHUncompressed,
+		31..=34 => SignatureType::P2PKHCompressed,
+		35..=38 => SignatureType::P2SHP2WPKH,
+		39..=42 => SignatureType::P2WPKH,
+		_ => return Err("InvalidHeader"),
+	};
+
+	let mut rsv = [0u8; 65];
+	rsv[..64].copy_from_slice(&sig[1..]);
+	rsv[64] = (sig[0] - 27) & 3;
+	Ok((sig_type, rsv))
+}
+
+/// Lower case hex encoding, used to make binary payloads signable as plain text
+pub fn to_hex(bytes: &[u8]) -> Vec<u8> {
+	const HEX_CHARS: &[u8] = b"0123456789abcdef";
+	let mut result = Vec::with_capacity(bytes.len() * 2);
+	for b in bytes {
+		result.push(HEX_CHARS[(b >> 4) as usize]);
+		result.push(HEX_CHARS[(b & 0x0f) as usize]);
+	}
+	result
+}
+
+// https://en.bitcoin.it/wiki/Protocol_documentation#Variable_length_integer
+pub fn compact_size(n: u64) -> Vec<u8> {
+	let mut result = Vec::new();
+	if n < 0xfd {
+		result.push(n as u8);
+	} else if n <= 0xffff {
+		result.push(0xfd);
+		result.extend_from_slice(&(n as u16).to_le_bytes());
+	} else if n <= 0xffff_ffff {
+		result.push(0xfe);
+		result.extend_from_slice(&(n as u32).to_le_bytes());
+	} else {
+		result.push(0xff);
+		result.extend_from_slice(&n.to_le_bytes());
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex::decode;
+
+	#[test]
+	fn correct_compact_size() {
+		assert_eq!(compact_size(0), vec![0]);
+		assert_eq!(compact_size(0xfc), vec![0xfc]);
+		assert_eq!(compact_size(0xfd), vec![0xfd, 0xfd, 0x00]);
+		assert_eq!(compact_size(0x1_0000), vec![0xfe, 0x00, 0x00, 0x01, 0x00]);
+	}
+
+	#[test]
+	fn correct_signed_message_hash() {
+		let hash = signed_message_hash(b"Hello World");
+
+		let expected =
+			decode("a7af0baad5ae99b97fc69b3a0d1abcf3ef17f131cc4776e1bc11933ec8550f49").unwrap();
+		assert_eq!(hash[..], expected[..]);
+	}
+
+	#[test]
+	fn parse_signature_header() {
+		let mut sig = [1u8; 65];
+
+		sig[0] = 28;
+		let (sig_type, rsv) = parse_signature(&sig).unwrap();
+		assert_eq!(sig_type, SignatureType::P2PKHUncompressed);
+		assert_eq!(rsv[64], 1);
+		assert_eq!(rsv[..64], [1u8; 64][..]);
+
+		sig[0] = 31;
+		assert_eq!(parse_signature(&sig).unwrap(), (SignatureType::P2PKHCompressed, rsv_with(0)));
+		sig[0] = 38;
+		assert_eq!(parse_signature(&sig).unwrap(), (SignatureType::P2SHP2WPKH, rsv_with(3)));
+		sig[0] = 40;
+		assert_eq!(parse_signature(&sig).unwrap(), (SignatureType::P2WPKH, rsv_with(1)));
+
+		sig[0] = 26;
+		assert!(parse_signature(&sig).is_err());
+		sig[0] = 43;
+		assert!(parse_signature(&sig).is_err());
+	}
+
+	#[test]
+	fn correct_to_hex() {
+		assert_eq!(to_hex(&[0x00, 0x1f, 0xab, 0xff]), b"001fabff".to_vec());
+	}
+
+	fn rsv_with(v: u8) -> [u8; 65] {
+		let mut rsv = [1u8; 65];
+		rsv[64] = v;
+		rsv
+	}
+}
@@ -1,3 +1,66 @@
 pub mod base58;
+pub mod bip322;
 pub mod legacy;
+pub mod message;
 pub mod witness;
+
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+
+/// The Bitcoin network whose addresses can be linked
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum BitcoinNetwork {
+	Mainnet,
+	Testnet,
+	Signet,
+	Regtest,
+}
+
+impl BitcoinNetwork {
+	/// Human-readable part of the segwit addresses
+	pub fn hrp(&self) -> &'static [u8] {
+		match self {
+			BitcoinNetwork::Mainnet => b"bc",
+			BitcoinNetwork::Testnet | BitcoinNetwork::Signet => b"tb",
+			BitcoinNetwork::Regtest => b"bcrt",
+		}
+	}
+
+	/// Base58Check version byte of P2PKH addresses
+	pub fn p2pkh_version(&self) -> u8 {
+		match self {
+			BitcoinNetwork::Mainnet => 0x00,
+			_ => 0x6f,
+		}
+	}
+
+	/// Base58Check version byte of P2SH addresses
+	pub fn p2sh_version(&self) -> u8 {
+		match self {
+			BitcoinNetwork::Mainnet => 0x05,
+			_ => 0xc4,
+		}
+	}
+
+	/// Leading characters of Base58Check encoded P2PKH addresses
+	pub fn p2pkh_prefixes(&self) -> &'static [u8] {
+		match self {
+			BitcoinNetwork::Mainnet => b"1",
+			_ => b"mn",
+		}
+	}
+
+	/// Leading characters of Base58Check encoded P2SH addresses
+	pub fn p2sh_prefixes(&self) -> &'static [u8] {
+		match self {
+			BitcoinNetwork::Mainnet => b"3",
+			_ => b"2",
+		}
+	}
+}
+
+impl Default for BitcoinNetwork {
+	fn default() -> Self {
+		BitcoinNetwork::Mainnet
+	}
+}
@@ -37,10 +37,38 @@ impl WitnessProgram {
 		let p5 = self.program.to_base32();
 		// let p5 = convert_bits(self.program.to_vec(), 8, 5, true)?;
 		data.extend_from_slice(&p5);
-		let b32 = data.encode(hrp)?;
+		let b32 = data.encode(hrp, Variant::for_version(self.version))?;
 		Ok(b32)
 	}
 
+	/// Decodes a SegWit Address into a Witness Program, following BIP-173 and BIP-350
+	pub fn from_address(hrp: &[u8], address: &[u8]) -> Result<Self, &'static str> {
+		let (decoded_hrp, data, variant) = decode(address)?;
+		if decoded_hrp != hrp {
+			return Err("InvalidHrp")
+		}
+		if data.is_empty() {
+			return Err("InvalidLength")
+		}
+
+		let version = data[0];
+		if version > 16 {
+			return Err("InvalidVersion")
+		}
+		if variant != Variant::for_version(version) {
+			return Err("InvalidVariant")
+		}
+
+		let program = from_base32(&data[1..])?;
+		if program.len() < 2 || program.len() > 40 {
+			return Err("InvalidProgramLength")
+		}
+		if version == 0 && program.len() != 20 && program.len() != 32 {
+			return Err("InvalidProgramLength")
+		}
+		Ok(WitnessProgram { version, program })
+	}
+
 	/// Extracts a WitnessProgram out of a provided script public key
 	pub fn from_scriptpubkey(pubkey: &[u8]) -> Result<Self, &'static str> {
 		// We need a version byte and a program length byte, with a program at
@@ -65,19 +93,45 @@ impl WitnessProgram {
 
 const SEP: u8 = b'1';
 const ALPHABET: &'static [u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// Checksum variant, version 0 witness programs use bech32 and later versions bech32m
+/// https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Variant {
+	Bech32,
+	Bech32m,
+}
+
+impl Variant {
+	pub fn for_version(version: u8) -> Self {
+		if version == 0 {
+			Variant::Bech32
+		} else {
+			Variant::Bech32m
+		}
+	}
+
+	fn constant(&self) -> u32 {
+		match self {
+			Variant::Bech32 => 1,
+			Variant::Bech32m => BECH32M_CONST,
+		}
+	}
+}
 
 pub trait Bech32 {
-	fn encode(&self, hrp: Vec<u8>) -> Result<Vec<u8>, &'static str>;
+	fn encode(&self, hrp: Vec<u8>, variant: Variant) -> Result<Vec<u8>, &'static str>;
 }
 
 impl Bech32 for [u8] {
-	fn encode(&self, hrp: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+	fn encode(&self, hrp: Vec<u8>, variant: Variant) -> Result<Vec<u8>, &'static str> {
 		if hrp.len() < 1 {
 			return Err("invalidData")
 		}
 
 		let mut combined: Vec<u8> = self.clone().to_vec();
-		combined.extend_from_slice(&create_checksum(&hrp, &self.to_vec()));
+		combined.extend_from_slice(&create_checksum(&hrp, &self.to_vec(), variant));
 		let mut encoded = hrp;
 		encoded.push(SEP);
 		for p in combined {
@@ -104,12 +158,71 @@ fn hrp_expand(hrp: &Vec<u8>) -> Vec<u8> {
 	v
 }
 
-fn create_checksum(hrp: &Vec<u8>, data: &Vec<u8>) -> Vec<u8> {
+/// Splits a bech32 or bech32m string into its hrp and 5-bit data part (checksum excluded)
+fn decode(address: &[u8]) -> Result<(Vec<u8>, Vec<u8>, Variant), &'static str> {
+	if address.len() < 8 || address.len() > 90 {
+		return Err("InvalidLength")
+	}
+	// Mixed case is not allowed
+	let has_lower = address.iter().any(|c| c.is_ascii_lowercase());
+	let has_upper = address.iter().any(|c| c.is_ascii_uppercase());
+	if has_lower && has_upper {
+		return Err("MixedCase")
+	}
+	let address = address.to_ascii_lowercase();
+
+	let sep = address.iter().rposition(|c| *c == SEP).ok_or("MissingSeparator")?;
+	if sep < 1 || sep + 7 > address.len() {
+		return Err("InvalidSeparatorPosition")
+	}
+	let hrp = address[..sep].to_vec();
+	if hrp.iter().any(|c| *c < 33 || *c > 126) {
+		return Err("InvalidHrp")
+	}
+
+	let mut data: Vec<u8> = Vec::new();
+	for c in &address[sep + 1..] {
+		let value = ALPHABET.iter().position(|a| a == c).ok_or("InvalidChar")?;
+		data.push(value as u8);
+	}
+
+	let mut values = hrp_expand(&hrp);
+	values.extend_from_slice(&data);
+	let variant = match polymod(values) {
+		1 => Variant::Bech32,
+		BECH32M_CONST => Variant::Bech32m,
+		_ => return Err("InvalidChecksum"),
+	};
+
+	data.truncate(data.len() - 6);
+	Ok((hrp, data, variant))
+}
+
+/// Converts 5-bit groups back into bytes, rejecting non-zero or oversized padding
+fn from_base32(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+	let mut acc: u32 = 0;
+	let mut bits: u32 = 0;
+	let mut result = Vec::new();
+	for value in data {
+		acc = (acc << 5) | *value as u32;
+		bits += 5;
+		if bits >= 8 {
+			bits -= 8;
+			result.push((acc >> bits) as u8);
+		}
+	}
+	if bits >= 5 || (acc << (8 - bits)) & 0xff != 0 {
+		return Err("InvalidPadding")
+	}
+	Ok(result)
+}
+
+fn create_checksum(hrp: &Vec<u8>, data: &Vec<u8>, variant: Variant) -> Vec<u8> {
 	let mut values: Vec<u8> = hrp_expand(hrp);
 	values.extend_from_slice(data);
 	// Pad with 6 zeros
 	values.extend_from_slice(&[0u8; 6]);
-	let plm: u32 = polymod(values) ^ 1;
+	let plm: u32 = polymod(values) ^ variant.constant();
 	let mut checksum: Vec<u8> = Vec::new();
 	for p in 0..6 {
 		checksum.push(((plm >> 5 * (5 - p)) & 0x1f) as u8);
@@ -213,12 +326,14 @@ impl ToBase32 for [u8] {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::btc::BitcoinNetwork;
 	use std::str::from_utf8;
 
 	#[test]
 	fn test_to_base32_basic() {
 		assert_eq!(
-			from_utf8(&vec![0x00, 0x01, 0x02].encode(b"bech32".to_vec()).unwrap()).unwrap(),
+			from_utf8(&vec![0x00, 0x01, 0x02].encode(b"bech32".to_vec(), Variant::Bech32).unwrap())
+				.unwrap(),
 			"bech321qpz4nc4pe"
 		);
 	}
@@ -234,7 +349,7 @@ mod tests {
 				],
 			),
 			(
-				"bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7k7grplx",
+				"bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
 				vec![
 					0x51, 0x28, 0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c,
 					0x45, 0xd1, 0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6, 0x75, 0x1e, 0x76, 0xe8,
@@ -242,9 +357,9 @@ mod tests {
 					0x43, 0x3b, 0xd6,
 				],
 			),
-			("BC1SW50QA3JX3S", vec![0x60, 0x02, 0x75, 0x1e]),
+			("BC1SW50QGDZ25J", vec![0x60, 0x02, 0x75, 0x1e]),
 			(
-				"bc1zw508d6qejxtdg4y5r3zarvaryvg6kdaj",
+				"bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
 				vec![
 					0x52, 0x10, 0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c,
 					0x45, 0xd1, 0xb3, 0xa3, 0x23,
@@ -265,6 +380,87 @@ mod tests {
 
 			let enc_address = enc_result.unwrap();
 			assert_eq!(address.to_lowercase(), from_utf8(&enc_address).unwrap().to_lowercase());
+
+			let dec_result = WitnessProgram::from_address(b"bc", address.as_bytes());
+			assert!(dec_result.is_ok());
+			assert_eq!(dec_result.unwrap().program, prog.program);
+		}
+	}
+
+	#[test]
+	fn valid_taproot_address() {
+		let address = b"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+		let prog = WitnessProgram::from_address(b"bc", address).unwrap();
+
+		assert_eq!(prog.version, 1);
+		assert_eq!(
+			prog.program,
+			vec![
+				0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87,
+				0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b,
+				0x16, 0xf8, 0x17, 0x98,
+			]
+		);
+		assert_eq!(prog.to_address(b"bc".to_vec()).unwrap(), address.to_vec());
+	}
+
+	#[test]
+	fn valid_network_address() {
+		let hrps: Vec<(BitcoinNetwork, &str, &str)> = vec![
+			(
+				BitcoinNetwork::Testnet,
+				"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+				"tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47zagq",
+			),
+			(
+				BitcoinNetwork::Signet,
+				"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+				"tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47zagq",
+			),
+			(
+				BitcoinNetwork::Regtest,
+				"bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+				"bcrt1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqc8gma6",
+			),
+		];
+		for (network, p2wpkh, p2tr) in hrps {
+			let hrp = network.hrp();
+			for address in &[p2wpkh, p2tr] {
+				let prog = WitnessProgram::from_address(hrp, address.as_bytes()).unwrap();
+				assert_eq!(prog.to_address(hrp.to_vec()).unwrap(), address.as_bytes().to_vec());
+				// addresses of other networks are rejected
+				assert!(WitnessProgram::from_address(b"bc", address.as_bytes()).is_err());
+			}
+		}
+	}
+
+	#[test]
+	fn invalid_address() {
+		let addresses: Vec<&str> = vec![
+			// Invalid human-readable part
+			"tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut",
+			// Invalid checksum (Bech32 instead of Bech32m)
+			"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+			// Invalid checksum (Bech32m instead of Bech32)
+			"BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+			"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+			// Invalid witness version
+			"BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R",
+			// Invalid program length
+			"bc1pw5dgrnzv",
+			// Mixed case
+			"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jJ0",
+			// Zero padding of more than 4 bits
+			"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf",
+			// Empty data section
+			"bc1gmk9yu",
+		];
+		for address in addresses {
+			assert!(
+				WitnessProgram::from_address(b"bc", address.as_bytes()).is_err(),
+				"{}",
+				address
+			);
 		}
 	}
 }
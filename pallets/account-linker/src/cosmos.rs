@@ -0,0 +1,84 @@
+//! Cosmos SDK addresses and ADR-036 off-chain signatures
+//! https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-036-arbitrary-signature.md
+
+use crate::btc::{
+	legacy::hash160,
+	witness::{Bech32, ToBase32, Variant},
+};
+use sp_std::prelude::*;
+
+const BASE64_ALPHABET: &'static [u8] =
+	b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Derives the bech32 address of a compressed secp256k1 public key, i.e. the bech32 encoding of
+/// ripemd160(sha256(pk)) with the human readable part of the chain (`cosmos`, `osmo`, `juno`...)
+pub fn address_from_pk(hrp: Vec<u8>, pk: &[u8]) -> Result<Vec<u8>, &'static str> {
+	hash160(pk).to_base32().encode(hrp, Variant::Bech32)
+}
+
+/// Assembles the amino JSON `StdSignDoc` of an ADR-036 `MsgSignData` message, as signed by the
+/// `signArbitrary` method of wallets like Keplr. Its sha256 hash is what the signature is over.
+pub fn adr036_sign_doc(signer: &[u8], data: &[u8]) -> Vec<u8> {
+	let mut doc = br#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":""#.to_vec();
+	doc.append(&mut to_base64(data));
+	doc.extend_from_slice(br#"","signer":""#);
+	doc.extend_from_slice(signer);
+	doc.extend_from_slice(br#""}}],"sequence":"0"}"#);
+	doc
+}
+
+/// Standard base64 encoding with padding
+pub fn to_base64(bytes: &[u8]) -> Vec<u8> {
+	let mut encoded = Vec::with_capacity((bytes.len() + 2) / 3 * 4);
+	for chunk in bytes.chunks(3) {
+		let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+		let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+		for i in 0..4 {
+			if i <= chunk.len() {
+				encoded.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]);
+			} else {
+				encoded.push(b'=');
+			}
+		}
+	}
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn correct_to_base64() {
+		// https://datatracker.ietf.org/doc/html/rfc4648#section-10
+		assert_eq!(to_base64(b""), b"".to_vec());
+		assert_eq!(to_base64(b"f"), b"Zg==".to_vec());
+		assert_eq!(to_base64(b"fo"), b"Zm8=".to_vec());
+		assert_eq!(to_base64(b"foo"), b"Zm9v".to_vec());
+		assert_eq!(to_base64(b"foob"), b"Zm9vYg==".to_vec());
+		assert_eq!(to_base64(b"fooba"), b"Zm9vYmE=".to_vec());
+		assert_eq!(to_base64(b"foobar"), b"Zm9vYmFy".to_vec());
+	}
+
+	#[test]
+	fn correct_address_from_pk() {
+		let pk = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+			.unwrap();
+		assert_eq!(
+			address_from_pk(b"cosmos".to_vec(), &pk),
+			Ok(b"cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c".to_vec())
+		);
+		assert_eq!(
+			address_from_pk(b"osmo".to_vec(), &pk),
+			Ok(b"osmo1w508d6qejxtdg4y5r3zarvary0c5xw7kjxy2e2".to_vec())
+		);
+	}
+
+	#[test]
+	fn correct_adr036_sign_doc() {
+		assert_eq!(
+			adr036_sign_doc(b"cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c", b"foobar"),
+			br#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"Zm9vYmFy","signer":"cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c"}}],"sequence":"0"}"#.to_vec()
+		);
+	}
+}
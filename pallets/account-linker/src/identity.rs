@@ -0,0 +1,158 @@
+//! The linked addresses of a Litentry account, as returned by the `AccountLinkerApi` runtime API
+
+use crate::{util_eth, EthAddress, LinkProof, PolkadotLinkRequest, SolanaAddress};
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// An address linked to a Litentry account in its stored form, e.g. to pick the link to label
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum LinkedAddress<AccountId> {
+	Ethereum(EthAddress),
+	// Base58Check or bech32 text
+	Bitcoin(Vec<u8>),
+	Tron(EthAddress),
+	Solana(SolanaAddress),
+	// bech32 text
+	Cosmos(Vec<u8>),
+	Polkadot(AccountId),
+}
+
+/// A linked address in its display encoding
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct IdentityLink<BlockNumber> {
+	/// The address text, e.g. `0x` prefixed hex for Ethereum and Base58Check for Tron
+	#[cfg_attr(feature = "std", serde(with = "text"))]
+	pub address: Vec<u8>,
+	pub linked_at: BlockNumber,
+	pub verified_at: BlockNumber,
+	/// Whether the link wasn't refreshed within `LinkValidityPeriod`
+	pub stale: bool,
+	pub proof: LinkProof,
+	#[cfg_attr(feature = "std", serde(with = "optional_text"))]
+	pub label: Option<Vec<u8>>,
+}
+
+/// A linked Litentry account
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PolkadotIdentityLink<AccountId, BlockNumber> {
+	pub account: AccountId,
+	pub linked_at: BlockNumber,
+	pub verified_at: BlockNumber,
+	/// Whether the link wasn't refreshed within `LinkValidityPeriod`
+	pub stale: bool,
+	pub proof: LinkProof,
+	#[cfg_attr(feature = "std", serde(with = "optional_text"))]
+	pub label: Option<Vec<u8>>,
+}
+
+/// A `link_polkadot` request sent by the account, waiting for `target` to accept it
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PendingPolkadotLink<AccountId, BlockNumber> {
+	pub target: AccountId,
+	pub request: PolkadotLinkRequest<BlockNumber>,
+}
+
+/// Everything linked to a Litentry account, in slot order
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct LinkedIdentity<AccountId, BlockNumber> {
+	pub ethereum: Vec<IdentityLink<BlockNumber>>,
+	pub bitcoin: Vec<IdentityLink<BlockNumber>>,
+	pub tron: Vec<IdentityLink<BlockNumber>>,
+	pub solana: Vec<IdentityLink<BlockNumber>>,
+	pub cosmos: Vec<IdentityLink<BlockNumber>>,
+	pub polkadot: Vec<PolkadotIdentityLink<AccountId, BlockNumber>>,
+	pub pending_polkadot: Vec<PendingPolkadotLink<AccountId, BlockNumber>>,
+}
+
+/// The EIP-712 domain of the `link_eth_eip712` typed data, for wallets to build it
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Eip712Domain {
+	/// The name of the pallet in the runtime
+	#[cfg_attr(feature = "std", serde(with = "text"))]
+	pub name: Vec<u8>,
+	#[cfg_attr(feature = "std", serde(with = "text"))]
+	pub version: Vec<u8>,
+	pub chain_id: u64,
+	/// The genesis hash, binding the signatures to this chain
+	#[cfg_attr(feature = "std", serde(with = "hex32"))]
+	pub salt: [u8; 32],
+}
+
+impl Eip712Domain {
+	/// The EIP-712 `domainSeparator`
+	pub fn separator(&self) -> [u8; 32] {
+		util_eth::eip712_domain_separator(&self.name, &self.version, self.chain_id, self.salt)
+	}
+}
+
+// (De)serialize the address text as a string rather than an array of bytes
+#[cfg(feature = "std")]
+mod text {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&String::from_utf8_lossy(bytes))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		String::deserialize(deserializer).map(String::into_bytes)
+	}
+}
+
+#[cfg(feature = "std")]
+mod optional_text {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(
+		bytes: &Option<Vec<u8>>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		match bytes {
+			Some(bytes) => super::text::serialize(bytes, serializer),
+			None => serializer.serialize_none(),
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Option<Vec<u8>>, D::Error> {
+		Option::<String>::deserialize(deserializer).map(|text| text.map(String::into_bytes))
+	}
+}
+
+// (De)serialize 32 bytes as `0x` prefixed hex
+#[cfg(feature = "std")]
+mod hex32 {
+	use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+		let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+		serializer.serialize_str(&format!("0x{}", hex))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+		let text = String::deserialize(deserializer)?;
+		let hex = text.strip_prefix("0x").unwrap_or(&text);
+		if hex.len() != 64 || !hex.is_ascii() {
+			return Err(D::Error::custom("expected 32 bytes of hex"))
+		}
+		let mut bytes = [0u8; 32];
+		for (i, byte) in bytes.iter_mut().enumerate() {
+			*byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(D::Error::custom)?;
+		}
+		Ok(bytes)
+	}
+}
@@ -1,13 +1,37 @@
 //! # AccountLinker Pallet
 //!
 //! The AccountLinker pallet provides functionality for linking a Litentry account to account at
-//! other networks. (currently support Ethereum (BSC), BTC and Polkadot ecosystem)
+//! other networks. (currently support Ethereum (BSC), BTC, Tron, Solana, Cosmos and Polkadot
+//! ecosystem)
 //!
 //! ## Overview
 //!
 //! The AccountLinker pallet stores the linking relation between Litentry accounts and accounts at other
 //! networks. It also offers extrinscs for user to update the linking relation. For each linking relation,
 //! user may choose to freshly link new account or replace an existing linked account with a new provided one.
+//! Each linked account and each pending `link_polkadot` or `link_eth_contract` request reserves a deposit from
+//! the Litentry account, which is returned once it is removed.
+//!
+//! Each slot of a linked address list is a `LinkRecord`, which keeps the block the address was linked at, the
+//! `LinkProof` signature scheme that proved its ownership and an optional label chosen by the user.
+//!
+//! With `Config::LinkValidityPeriod` set, a link goes stale once its ownership was last proven that many blocks
+//! ago. `on_initialize` announces it with `LinkStale` and `is_link_stale` reports it until `refresh_link` proves
+//! the ownership again. Stale links are kept, their consumers skip them, e.g. the offchain worker only queries
+//! the balances of `fresh_eth_addresses`.
+//!
+//! Every signed link message starts with the `LinkDomain` tag of the chain, e.g. its genesis hash and the index
+//! of the pallet, so that a signature collected on a chain can't be replayed on another one. The EIP-712 typed
+//! data of `link_eth_eip712` is bound to the chain by the genesis hash salt of its domain instead. The
+//! `AccountLinkerApi` runtime API returns the exact messages to sign, and the EIP-712 domain and hashes.
+//!
+//! The `AccountLinkerApi` runtime API also returns the `LinkedIdentity` of an account: all its linked addresses in
+//! their display encodings with their `LinkRecord` details and its pending `link_polkadot` requests. The
+//! `pallet-account-linker-rpc` crate serves it to frontends as `accountLinker_linkedIdentity`.
+//!
+//! Other pallets read the links through the `LinkedAccountsProvider` and `ContractLinkAttestation` traits
+//! implemented by `Pallet`, taking them as associated types of their `Config` so that they can be mocked.
+//! They can react to links and unlinks as `OnLinkChanged` handlers, a tuple of handlers is also one.
 //!
 //! ## Interface
 //!
@@ -15,11 +39,33 @@
 //!
 //! * `link_eth` - Link an Ethereum address to a Litentry account providing a proof signature
 //! from the private key of that Ethereum address.
+//! * `link_eth_eip712` - Link an Ethereum address to a Litentry account providing an EIP-712
+//! typed data signature from the private key of that Ethereum address.
+//! * `link_eth_contract` - Request to link a smart contract wallet to a Litentry account providing
+//! an EIP-1271 contract signature, the link is made once enough offchain workers attest it.
+//! * `cancel_eth_contract_link` - Cancel a pending `link_eth_contract` request.
 //! * `link_btc` - Link an BTC address to a Litentry account providing a proof signature
-//! from the private key of that BTC address.
+//! from the private key of that BTC address, in Bitcoin Signed Message (BIP-137) or BIP-322 format.
+//! * `link_tron` - Link a Tron address to a Litentry account providing a proof signature
+//! from the private key of that Tron address.
+//! * `link_solana` - Link a Solana address to a Litentry account providing an ed25519 signature
+//! from the private key of that Solana address.
+//! * `link_cosmos` - Link a Cosmos SDK address to a Litentry account providing an ADR-036
+//! signature from the private key of that Cosmos SDK address.
 //! * `link_polkadot` - Initiate a link request to link a Litentry address to another Litentry address
 //! * `accept_polkadot` - Accept a pending `link_polkadot` request to link a Litentry address
 //! to another Litentry address.
+//! * `cancel_polkadot_link` - Cancel a pending `link_polkadot` request.
+//! * `link_polkadot_signed` - Link a Litentry address to a Litentry account providing a signature
+//! from that Litentry address, without `link_polkadot` and `accept_polkadot`.
+//! * `unlink_eth` - Remove a linked Ethereum address from a Litentry account.
+//! * `unlink_btc` - Remove a linked BTC address from a Litentry account.
+//! * `unlink_tron` - Remove a linked Tron address from a Litentry account.
+//! * `unlink_solana` - Remove a linked Solana address from a Litentry account.
+//! * `unlink_cosmos` - Remove a linked Cosmos SDK address from a Litentry account.
+//! * `unlink_polkadot` - Remove a linked Litentry address from a Litentry account.
+//! * `set_link_label` - Set or clear the label of a linked address.
+//! * `refresh_link` - Prove the ownership of a linked address again, so that it doesn't go stale.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -36,37 +82,353 @@ mod tests;
 
 mod benchmarking;
 mod btc;
+mod cosmos;
+mod identity;
+pub mod migrations;
+mod traits;
 mod util_eth;
 pub mod weights;
 
+pub use btc::BitcoinNetwork;
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::Get, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+pub use identity::{
+	Eip712Domain, IdentityLink, LinkedAddress, LinkedIdentity, PendingPolkadotLink,
+	PolkadotIdentityLink,
+};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{traits::Member, RuntimeDebug};
+use sp_std::prelude::*;
+pub use traits::{ContractLinkAttestation, LinkedAccountsProvider, OnLinkChanged};
+
 type EthAddress = [u8; 20];
+// ed25519 public key
+type SolanaAddress = [u8; 32];
 // rsv signature
 type Signature = [u8; 65];
 
+// Start of every message signed to link an address
+const LINK_MESSAGE_PREFIX: &[u8] = b"Link Litentry: ";
+
+// A value placed in storage that represents the current version of the storage. This value
+// is used by the `on_runtime_upgrade` logic to determine whether we run storage migration logic.
+// New chains start at the latest release from the genesis build, which must be bumped with every
+// new release.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	V1_0_0,
+	V2_0_0,
+	V3_0_0,
+	V4_0_0,
+	V5_0_0,
+	V6_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+/// Where a newly linked address goes in the list of linked addresses of an account
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum LinkMode {
+	/// Add the address to a free slot, fails when all the slots are taken
+	Append,
+	/// Replace the address linked at the given slot, which must be taken
+	Replace(u32),
+}
+
+impl Default for LinkMode {
+	fn default() -> Self {
+		LinkMode::Append
+	}
+}
+
+/// Whether an Ethereum, BTC, Tron, Solana or Cosmos address can be linked to several Litentry accounts
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum LinkExclusivity {
+	/// Any number of Litentry accounts can link the same address
+	Shared,
+	/// Linking an address that is already linked to another Litentry account fails
+	Reject,
+	/// The address is unlinked from the other Litentry accounts, the newest prover keeps it
+	Transfer,
+}
+
+impl Default for LinkExclusivity {
+	fn default() -> Self {
+		LinkExclusivity::Shared
+	}
+}
+
+/// A pending `link_polkadot` request, waiting for the target account to accept it
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PolkadotLinkRequest<BlockNumber> {
+	/// How the target account will be linked once it accepts the request
+	pub mode: LinkMode,
+	/// The block number from which the request can no longer be accepted
+	pub expiring_block_number: BlockNumber,
+}
+
+/// The signature scheme that proved the ownership of a linked address
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum LinkProof {
+	/// Linked before the proof was recorded
+	Unknown,
+	/// Ethereum `personal_sign` message, `link_eth`
+	PersonalSign,
+	/// EIP-712 typed data, `link_eth_eip712`
+	Eip712,
+	/// EIP-1271 contract signature attested by the offchain workers, `link_eth_contract`
+	Eip1271,
+	/// Bitcoin Signed Message, `link_btc` with a legacy or segwit v0 address
+	Bip137,
+	/// BIP-322 simple signature, `link_btc` with a taproot address
+	Bip322,
+	/// TIP-191 Tron signed message, `link_tron`
+	Tip191,
+	/// ed25519 signature of the text message, `link_solana`
+	Ed25519,
+	/// ADR-036 sign doc, `link_cosmos`
+	Adr036,
+	/// Signature of the linked Litentry account, `link_polkadot_signed`
+	MultiSignature,
+	/// Accepted by the linked Litentry account, `link_polkadot` then `accept_polkadot`
+	Accepted,
+}
+
+impl Default for LinkProof {
+	fn default() -> Self {
+		LinkProof::Unknown
+	}
+}
+
+/// An address linked to a Litentry account, in a slot of its linked address list
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LinkRecord<Address, BlockNumber> {
+	pub address: Address,
+	/// The block the address was linked at, the block of the v5 migration for the addresses
+	/// linked before it was recorded
+	pub linked_at: BlockNumber,
+	/// The block the ownership of the address was last proven at, by linking it or with
+	/// `refresh_link`. The link goes stale `Config::LinkValidityPeriod` blocks later.
+	pub verified_at: BlockNumber,
+	/// How the ownership of the address was proven
+	pub proof: LinkProof,
+	/// A label set by the Litentry account with `set_link_label`
+	pub label: Option<Vec<u8>>,
+}
+
+/// A pending `link_eth_contract` request, waiting for the offchain workers to attest that the
+/// contract accepts its signature
+#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound)]
+pub struct EthContractLinkRequest<
+	AccountId: Member,
+	BlockNumber: Member,
+	MaxSignatureLen: Get<u32>,
+	Quorum: Get<u32>,
+> {
+	/// How the contract will be linked once the signature is attested
+	pub mode: LinkMode,
+	/// The block number from which the request can no longer be attested
+	pub expiring_block_number: BlockNumber,
+	/// The hash passed to the `isValidSignature` method of the contract
+	pub hash: [u8; 32],
+	/// The EIP-1271 signature passed to the `isValidSignature` method of the contract
+	pub signature: BoundedVec<u8, MaxSignatureLen>,
+	/// The attesters that found the signature valid, the request is resolved once there are
+	/// `Quorum` of them
+	pub approvals: BoundedVec<AccountId, Quorum>,
+	/// The attesters that found the signature invalid
+	pub rejections: BoundedVec<AccountId, Quorum>,
+}
+
+/// The `EthContractLinkRequest` of the runtime
+pub type EthContractLinkRequestOf<T> = EthContractLinkRequest<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	<T as Config>::MaxContractSignatureLen,
+	<T as Config>::ContractLinkQuorum,
+>;
+
+/// The default `LinkDomain`: the genesis hash followed by the index of the pallet in the runtime
+pub struct GenesisHashAndPalletIndex<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> Get<Vec<u8>> for GenesisHashAndPalletIndex<T> {
+	fn get() -> Vec<u8> {
+		use frame_support::traits::PalletInfoAccess;
+		use sp_runtime::traits::Zero;
+
+		let mut domain =
+			<frame_system::Pallet<T>>::block_hash(T::BlockNumber::zero()).as_ref().to_vec();
+		domain.push(<Pallet<T> as PalletInfoAccess>::index() as u8);
+		domain
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use crate::*;
-	use btc::{base58::ToBase58, witness::WitnessProgram};
+	use btc::{base58::ToBase58, message::SignatureType, witness::WitnessProgram};
 	use codec::Encode;
-	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_support::{
+		dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo},
+		pallet_prelude::*,
+		traits::{Currency, PalletInfoAccess, ReservableCurrency, SortedMembers},
+		BoundedVec,
+	};
 	use frame_system::{ensure_signed, pallet_prelude::*};
-	use sp_io::crypto::secp256k1_ecdsa_recover_compressed;
-	use sp_std::prelude::*;
+	use sp_io::crypto::{
+		ed25519_verify, secp256k1_ecdsa_recover, secp256k1_ecdsa_recover_compressed,
+	};
+	use sp_runtime::{
+		app_crypto::ed25519,
+		traits::{IdentifyAccount, One, Saturating, UniqueSaturatedInto, Verify, Zero},
+	};
+	use sp_std::{convert::TryInto, prelude::*};
 	use weights::WeightInfo;
-	pub const EXPIRING_BLOCK_NUMBER_MAX: u32 = 10 * 60 * 24 * 30; // 30 days for 6s per block
-	pub const MAX_ETH_LINKS: usize = 3;
-	pub const MAX_BTC_LINKS: usize = 3;
-	pub const MAX_POLKADOT_LINKS: usize = 3;
+	pub const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	enum BTCAddrType {
 		Legacy,
+		// P2SH-P2WPKH
+		NestedSegwit,
 		Segwit,
+		// P2TR
+		Taproot,
 	}
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type WeightInfo: WeightInfo;
+
+		/// The currency in which the link deposits are reserved
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The deposit reserved from a Litentry account for each address linked to it
+		#[pallet::constant]
+		type LinkDeposit: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved from a Litentry account for each of its pending `link_polkadot`
+		/// requests
+		#[pallet::constant]
+		type PendingDeposit: Get<BalanceOf<Self>>;
+
+		/// The tag that starts the signed payload of every link message, binding the signatures
+		/// to this chain, e.g. `GenesisHashAndPalletIndex`.
+		///
+		/// The EIP-712 typed data of `link_eth_eip712` doesn't carry it, it binds the chain
+		/// through the salt of its domain instead, the genesis hash, see `eip712_domain`.
+		type LinkDomain: Get<Vec<u8>>;
+
+		/// The chain id used in the EIP-712 domain of `link_eth_eip712` signatures
+		#[pallet::constant]
+		type EthChainId: Get<u64>;
+
+		/// The Bitcoin network whose addresses are accepted by `link_btc`
+		#[pallet::constant]
+		type BitcoinNetwork: Get<BitcoinNetwork>;
+
+		/// Whether an Ethereum, BTC, Tron, Solana or Cosmos address can be linked to several Litentry accounts
+		#[pallet::constant]
+		type LinkExclusivity: Get<LinkExclusivity>;
+
+		/// The most Litentry accounts an address is transferred from by a single link call under
+		/// `LinkExclusivity::Transfer`. Linking an address linked to more accounts, e.g. while it
+		/// was shared, fails with `TooManyDisplacedAccounts` until some of them unlink it.
+		#[pallet::constant]
+		type MaxDisplacedAccounts: Get<u32>;
+
+		/// The signature of a Litentry address consenting to be linked by `link_polkadot_signed`
+		type PolkadotSignature: Verify<Signer = Self::PolkadotSigner> + Parameter;
+
+		/// The signer of a `PolkadotSignature`, identifying a Litentry address
+		type PolkadotSigner: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// The human readable part of the bech32 Cosmos SDK addresses accepted by `link_cosmos`,
+		/// e.g. `cosmos`, `osmo` or `juno`
+		#[pallet::constant]
+		type CosmosHrp: Get<Vec<u8>>;
+
+		/// The maximum number of Ethereum addresses linked to a Litentry account
+		#[pallet::constant]
+		type MaxEthLinks: Get<u32>;
+
+		/// The maximum number of BTC addresses linked to a Litentry account
+		#[pallet::constant]
+		type MaxBtcLinks: Get<u32>;
+
+		/// The maximum number of Tron addresses linked to a Litentry account
+		#[pallet::constant]
+		type MaxTronLinks: Get<u32>;
+
+		/// The maximum number of Solana addresses linked to a Litentry account
+		#[pallet::constant]
+		type MaxSolanaLinks: Get<u32>;
+
+		/// The maximum number of Cosmos SDK addresses linked to a Litentry account
+		#[pallet::constant]
+		type MaxCosmosLinks: Get<u32>;
+
+		/// The maximum number of Litentry addresses linked to a Litentry account
+		#[pallet::constant]
+		type MaxPolkadotLinks: Get<u32>;
+
+		/// How far from the current block the expiring block number of a link request can be
+		#[pallet::constant]
+		type ExpiringBlockNumberMax: Get<Self::BlockNumber>;
+
+		/// The maximum number of blocks and expired `link_polkadot` requests the sweeper goes
+		/// through in `on_initialize`, and as many for the `link_eth_contract` requests
+		#[pallet::constant]
+		type PendingSweepLimit: Get<u32>;
+
+		/// The accounts allowed to attest the EIP-1271 signatures of `link_eth_contract` requests,
+		/// i.e. the ones of the offchain workers
+		type ContractLinkAttesters: SortedMembers<Self::AccountId>;
+
+		/// The number of matching attestations that accept or reject a `link_eth_contract` request,
+		/// which bounds the attestations a request holds
+		#[pallet::constant]
+		type ContractLinkQuorum: Get<u32>;
+
+		/// The maximum length of the EIP-1271 signature of a `link_eth_contract` request
+		#[pallet::constant]
+		type MaxContractSignatureLen: Get<u32>;
+
+		/// The maximum length of the label of a linked address, see `set_link_label`
+		#[pallet::constant]
+		type MaxLinkLabelLen: Get<u32>;
+
+		/// The number of blocks after which a link goes stale unless its ownership is proven again
+		/// with `refresh_link`, `None` for links that never go stale
+		#[pallet::constant]
+		type LinkValidityPeriod: Get<Option<Self::BlockNumber>>;
+
+		/// The maximum number of blocks and stale link entries the sweeper goes through in
+		/// `on_initialize`
+		#[pallet::constant]
+		type StaleSweepLimit: Get<u32>;
+
+		/// Notified when an address is linked to an account, and when it's no longer linked to it
+		/// because it was unlinked, replaced or transferred to another account.
+		///
+		/// A link call notifies it at most `MaxDisplacedAccounts + 2` times and an unlink call
+		/// once. Its weight is measured along with the calls by the benchmarks of the runtime,
+		/// whose link benchmarks replace a slot and transfer the address from the most accounts.
+		type OnLinkChanged: OnLinkChanged<Self::AccountId>;
 	}
 
 	#[pallet::event]
@@ -79,6 +441,63 @@ pub mod pallet {
 		BtcAddressLinked(T::AccountId, Vec<u8>),
 		/// Polkadot address successfully linked. \[Lintentry account, Polkadot account\]
 		PolkadotAddressLinked(T::AccountId, T::AccountId),
+		/// Ethereum address successfully unlinked. \[Litentry account, Ethereum account\]
+		EthAddressUnlinked(T::AccountId, Vec<u8>),
+		/// BTC address successfully unlinked. \[Litentry account, BTC account\]
+		BtcAddressUnlinked(T::AccountId, Vec<u8>),
+		/// Polkadot address successfully unlinked. \[Litentry account, Polkadot account\]
+		PolkadotAddressUnlinked(T::AccountId, T::AccountId),
+		/// Ethereum address unlinked because another Litentry account proved its ownership.
+		/// \[displaced Litentry account, new Litentry account, Ethereum account\]
+		EthAddressTransferred(T::AccountId, T::AccountId, Vec<u8>),
+		/// A `link_polkadot` request was cancelled by its sender. \[Litentry account, target account\]
+		PolkadotLinkCancelled(T::AccountId, T::AccountId),
+		/// A `link_polkadot` request expired without being accepted.
+		/// \[Litentry account, target account\]
+		PolkadotLinkExpired(T::AccountId, T::AccountId),
+		/// BTC address unlinked because another Litentry account proved its ownership.
+		/// \[displaced Litentry account, new Litentry account, BTC account\]
+		BtcAddressTransferred(T::AccountId, T::AccountId, Vec<u8>),
+		/// A `link_eth_contract` request is waiting for attestations.
+		/// \[Litentry account, Ethereum contract account\]
+		EthContractLinkRequested(T::AccountId, Vec<u8>),
+		/// A `link_eth_contract` request was cancelled by its sender.
+		/// \[Litentry account, Ethereum contract account\]
+		EthContractLinkCancelled(T::AccountId, Vec<u8>),
+		/// A `link_eth_contract` request was rejected by a quorum of attesters.
+		/// \[Litentry account, Ethereum contract account\]
+		EthContractLinkRejected(T::AccountId, Vec<u8>),
+		/// A `link_eth_contract` request expired before reaching a quorum.
+		/// \[Litentry account, Ethereum contract account\]
+		EthContractLinkExpired(T::AccountId, Vec<u8>),
+		/// Tron address successfully linked. \[Lintentry account, Base58Check Tron account\]
+		TronAddressLinked(T::AccountId, Vec<u8>),
+		/// Tron address successfully unlinked. \[Litentry account, Base58Check Tron account\]
+		TronAddressUnlinked(T::AccountId, Vec<u8>),
+		/// Tron address unlinked because another Litentry account proved its ownership.
+		/// \[displaced Litentry account, new Litentry account, Base58Check Tron account\]
+		TronAddressTransferred(T::AccountId, T::AccountId, Vec<u8>),
+		/// Solana address successfully linked. \[Lintentry account, base58 Solana account\]
+		SolanaAddressLinked(T::AccountId, Vec<u8>),
+		/// Solana address successfully unlinked. \[Litentry account, base58 Solana account\]
+		SolanaAddressUnlinked(T::AccountId, Vec<u8>),
+		/// Solana address unlinked because another Litentry account proved its ownership.
+		/// \[displaced Litentry account, new Litentry account, base58 Solana account\]
+		SolanaAddressTransferred(T::AccountId, T::AccountId, Vec<u8>),
+		/// Cosmos SDK address successfully linked. \[Lintentry account, Cosmos account\]
+		CosmosAddressLinked(T::AccountId, Vec<u8>),
+		/// Cosmos SDK address successfully unlinked. \[Litentry account, Cosmos account\]
+		CosmosAddressUnlinked(T::AccountId, Vec<u8>),
+		/// Cosmos SDK address unlinked because another Litentry account proved its ownership.
+		/// \[displaced Litentry account, new Litentry account, Cosmos account\]
+		CosmosAddressTransferred(T::AccountId, T::AccountId, Vec<u8>),
+		/// The label of a linked address was set or cleared. \[Litentry account, linked address\]
+		LinkLabelSet(T::AccountId, LinkedAddress<T::AccountId>),
+		/// The ownership of a linked address was proven again. \[Litentry account, linked address\]
+		LinkRefreshed(T::AccountId, LinkedAddress<T::AccountId>),
+		/// A linked address wasn't refreshed within `LinkValidityPeriod`.
+		/// \[Litentry account, linked address\]
+		LinkStale(T::AccountId, LinkedAddress<T::AccountId>),
 	}
 
 	#[pallet::error]
@@ -89,278 +508,2400 @@ pub mod pallet {
 		LinkRequestExpired,
 		// Provided address mismatch the address recovered from signature recovery
 		UnexpectedAddress,
-		// Unexpected ethereum message length error
-		UnexpectedEthMsgLength,
 		// Invalid BTC address to link
 		InvalidBTCAddress,
 		// Expiration block number is too far away from now
 		InvalidExpiringBlockNumber,
 		// Try to resolve a wrong link_polkadot request
 		WrongPendingRequest,
+		// The address to unlink is not linked to the account
+		LinkNotFound,
+		// The signed link nonce has already been used or is not the next one
+		StaleLinkNonce,
+		// The BIP-137 signature header byte is out of range
+		InvalidBTCSignature,
+		// The BIP-137 signature header doesn't match the type of the BTC address
+		UnexpectedBTCSignatureType,
+		// The BIP-322 Schnorr signature doesn't verify against the Taproot address
+		SchnorrVerifyFailure,
+		// All the link slots are taken, an existing link has to be replaced or removed first
+		LinkSlotsFull,
+		// The link slot to replace is not taken
+		InvalidLinkIndex,
+		// The signature doesn't verify against the Litentry address to link
+		InvalidPolkadotSignature,
+		// The address is already linked to another Litentry account
+		AddressAlreadyLinked,
+		// The ed25519 signature doesn't verify against the Solana address to link
+		InvalidSolanaSignature,
+		// Invalid Cosmos SDK address to link
+		InvalidCosmosAddress,
+		// The EIP-1271 signature is longer than `MaxContractSignatureLen`
+		ContractSignatureTooLong,
+		// The account isn't allowed to attest `link_eth_contract` requests
+		NotContractLinkAttester,
+		// The attester has already attested the `link_eth_contract` request
+		AlreadyAttested,
+		// The label is longer than `MaxLinkLabelLen`
+		LinkLabelTooLong,
+		// The signature doesn't have the length of the signatures of the linked address network
+		InvalidSignatureLength,
+		// The address is already linked to the account
+		AlreadyLinked,
+		// A Litentry account can't be linked to itself
+		LinkToSelf,
+		// The address is linked to more accounts than a link call transfers it from
+		TooManyDisplacedAccounts,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			Self::sweep_expired_polkadot_pending(now)
+				.saturating_add(Self::sweep_expired_eth_contract_pending(now))
+				.saturating_add(Self::sweep_stale_links(now))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v2::migrate::<T>()
+				.saturating_add(migrations::v3::migrate::<T>())
+				.saturating_add(migrations::v4::migrate::<T>())
+				.saturating_add(migrations::v5::migrate::<T>())
+				.saturating_add(migrations::v6::migrate::<T>())
+		}
+
+		fn integrity_test() {
+			assert!(T::MaxEthLinks::get() > 0, "MaxEthLinks must allow at least one link");
+			assert!(T::MaxBtcLinks::get() > 0, "MaxBtcLinks must allow at least one link");
+			assert!(T::MaxTronLinks::get() > 0, "MaxTronLinks must allow at least one link");
+			assert!(T::MaxSolanaLinks::get() > 0, "MaxSolanaLinks must allow at least one link");
+			assert!(T::MaxCosmosLinks::get() > 0, "MaxCosmosLinks must allow at least one link");
+			assert!(!T::CosmosHrp::get().is_empty(), "CosmosHrp must not be empty");
+			assert!(
+				T::MaxPolkadotLinks::get() > 0,
+				"MaxPolkadotLinks must allow at least one link"
+			);
+			assert!(T::PendingSweepLimit::get() > 0, "PendingSweepLimit must allow some progress");
+			assert!(T::ContractLinkQuorum::get() > 0, "ContractLinkQuorum must not be zero");
+			assert!(
+				T::LinkValidityPeriod::get().map_or(true, |period| !period.is_zero()),
+				"LinkValidityPeriod must not be zero"
+			);
+			assert!(T::StaleSweepLimit::get() > 0, "StaleSweepLimit must allow some progress");
+		}
+	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
-	#[pallet::getter(fn eth_addresses)]
-	pub(super) type EthereumLink<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<EthAddress>, ValueQuery>;
+	#[pallet::getter(fn eth_links)]
+	pub(super) type EthereumLink<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<LinkRecord<EthAddress, T::BlockNumber>, T::MaxEthLinks>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn btc_links)]
+	pub(super) type BitcoinLink<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<LinkRecord<Vec<u8>, T::BlockNumber>, T::MaxBtcLinks>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn tron_links)]
+	pub(super) type TronLink<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<LinkRecord<EthAddress, T::BlockNumber>, T::MaxTronLinks>,
+		ValueQuery,
+	>;
 
 	#[pallet::storage]
-	#[pallet::getter(fn btc_addresses)]
-	pub(super) type BitcoinLink<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<Vec<u8>>, ValueQuery>;
+	#[pallet::getter(fn solana_links)]
+	pub(super) type SolanaLink<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<LinkRecord<SolanaAddress, T::BlockNumber>, T::MaxSolanaLinks>,
+		ValueQuery,
+	>;
 
 	#[pallet::storage]
-	#[pallet::getter(fn polkadot_addresses)]
-	pub(super) type PolkadotLink<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<T::AccountId>, ValueQuery>;
+	#[pallet::getter(fn cosmos_links)]
+	pub(super) type CosmosLink<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<LinkRecord<Vec<u8>, T::BlockNumber>, T::MaxCosmosLinks>,
+		ValueQuery,
+	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn polkadot_links)]
+	pub(super) type PolkadotLink<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<LinkRecord<T::AccountId, T::BlockNumber>, T::MaxPolkadotLinks>,
+		ValueQuery,
+	>;
+
+	/// The pending `link_polkadot` requests, keyed by the account that sent them and the account
+	/// that has to accept them
 	#[pallet::storage]
 	#[pallet::getter(fn polkadot_pending)]
-	pub(super) type PolkadotPending<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, (T::AccountId, u32), ValueQuery>;
+	pub(super) type PolkadotPending<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		PolkadotLinkRequest<T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// The pending `link_polkadot` requests grouped by expiring block number, so that the expired
+	/// ones can be removed by `on_initialize`
+	#[pallet::storage]
+	pub(super) type PolkadotPendingExpiry<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		(T::AccountId, T::AccountId),
+		(),
+		OptionQuery,
+	>;
+
+	/// The first expiring block number whose pending `link_polkadot` requests haven't all been
+	/// removed yet
+	#[pallet::storage]
+	pub(super) type PolkadotPendingSweep<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The links grouped by the block they go stale at, so that `on_initialize` can announce them.
+	/// The entries of unlinked and refreshed links are dropped once reached.
+	#[pallet::storage]
+	pub(super) type LinkExpiry<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		(T::AccountId, LinkedAddress<T::AccountId>),
+		(),
+		OptionQuery,
+	>;
+
+	/// The first block whose `LinkExpiry` entries haven't all been gone through yet
+	#[pallet::storage]
+	pub(super) type LinkExpirySweep<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The pending `link_eth_contract` requests, keyed by the account that sent them and the
+	/// contract to link
+	#[pallet::storage]
+	#[pallet::getter(fn eth_contract_pending)]
+	pub(super) type EthContractPending<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		EthAddress,
+		EthContractLinkRequestOf<T>,
+		OptionQuery,
+	>;
+
+	/// The pending `link_eth_contract` requests grouped by expiring block number, so that the
+	/// expired ones can be removed by `on_initialize`
+	#[pallet::storage]
+	pub(super) type EthContractPendingExpiry<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		(T::AccountId, EthAddress),
+		(),
+		OptionQuery,
+	>;
+
+	/// The first expiring block number whose pending `link_eth_contract` requests haven't all
+	/// been removed yet
+	#[pallet::storage]
+	pub(super) type EthContractPendingSweep<T: Config> =
+		StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Reverse index of `EthereumLink`: the Litentry accounts an Ethereum address is linked to
+	#[pallet::storage]
+	pub(super) type EthereumLinkAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EthAddress,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Reverse index of `BitcoinLink`: the Litentry accounts a BTC address is linked to
+	#[pallet::storage]
+	pub(super) type BitcoinLinkAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Reverse index of `TronLink`: the Litentry accounts a Tron address is linked to
+	#[pallet::storage]
+	pub(super) type TronLinkAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EthAddress,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Reverse index of `SolanaLink`: the Litentry accounts a Solana address is linked to
+	#[pallet::storage]
+	pub(super) type SolanaLinkAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		SolanaAddress,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Reverse index of `CosmosLink`: the Litentry accounts a Cosmos SDK address is linked to
+	#[pallet::storage]
+	pub(super) type CosmosLinkAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// Reverse index of `PolkadotLink`: the Litentry accounts a Litentry address is linked to
+	#[pallet::storage]
+	pub(super) type PolkadotLinkAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	/// The nonce that has to be signed in the next link request of an account. It is increased on
+	/// every successful `link_eth`, `link_eth_eip712`, `link_eth_contract`, `link_btc`, `link_tron`,
+	/// `link_solana`, `link_cosmos` and `link_polkadot_signed`.
+	#[pallet::storage]
+	#[pallet::getter(fn link_nonce)]
+	pub(super) type LinkNonce<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The total deposit reserved from a Litentry account for its links and pending requests,
+	/// nothing more than this is ever unreserved
+	#[pallet::storage]
+	#[pallet::getter(fn deposits)]
+	pub(super) type Deposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+	/// Storage version of the pallet, used by `on_runtime_upgrade` to run the migrations once
+	#[pallet::storage]
+	pub(super) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			// A new chain starts with the storage layout of the latest release, there is
+			// nothing for `on_runtime_upgrade` to migrate
+			StorageVersion::<T>::put(Releases::V6_0_0);
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Link an Ethereum address to the origin Litentry account providing a proof signature from the private key
 		/// of that Ethereum address.
 		///
-		/// The runtime needs to ensure that a malicious index can be handled correctly.
-		/// Currently, when vec.len > MAX_ETH_LINKS, replacement will always happen at the final index.
-		/// Otherwise it will use the next new slot unless index is valid against a currently available slot.
+		/// `LinkMode::Append` fails with `LinkSlotsFull` once all the slots are taken, an address
+		/// linked before is only ever overwritten by an explicit `LinkMode::Replace`.
 		///
 		/// Parameters:
-		/// - `index`: The index of the linked Ethereum address that the user wants to replace with.
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
 		/// - `addr_expected`: The intended Ethereum address to link to the origin's Litentry address
 		/// - `expiring_block_number`: The block number after which this link request will expire
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
 		/// - `sig`: The rsv-signature generated by the private key of the addr_expected
 		///
 		/// Emits `EthAddressLinked` event when successful.
-		#[pallet::weight(T::WeightInfo::link_eth())]
+		#[pallet::weight(T::WeightInfo::link_eth(T::MaxDisplacedAccounts::get()))]
 		pub fn link_eth(
 			origin: OriginFor<T>,
-			index: u32,
+			mode: LinkMode,
 			addr_expected: EthAddress,
 			expiring_block_number: T::BlockNumber,
+			nonce: u32,
 			sig: Signature,
 		) -> DispatchResultWithPostInfo {
 			let account = ensure_signed(origin)?;
 
-			let current_block_number = <frame_system::Pallet<T>>::block_number();
-			ensure!(expiring_block_number > current_block_number, Error::<T>::LinkRequestExpired);
-			ensure!(
-				(expiring_block_number - current_block_number) <
-					T::BlockNumber::from(EXPIRING_BLOCK_NUMBER_MAX),
-				Error::<T>::InvalidExpiringBlockNumber
-			);
-
-			let bytes = Self::generate_raw_message(&account, expiring_block_number);
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
 
-			let hash =
-				util_eth::eth_data_hash(bytes).map_err(|_| Error::<T>::UnexpectedEthMsgLength)?;
-
-			let mut msg = [0u8; 32];
-			msg[..32].copy_from_slice(&hash[..32]);
-
-			let addr =
-				util_eth::addr_from_sig(msg, sig).map_err(|_| Error::<T>::EcdsaRecoverFailure)?;
-			ensure!(addr == addr_expected, Error::<T>::UnexpectedAddress);
+			let bytes = Self::generate_raw_message(&account, expiring_block_number, nonce);
 
-			EthereumLink::<T>::mutate(&account, |addrs| {
-				let index = index as usize;
-				// NOTE: allow linking `MAX_ETH_LINKS` eth addresses.
-				if (index >= addrs.len()) && (addrs.len() != MAX_ETH_LINKS) {
-					addrs.push(addr.clone());
-				} else if (index >= addrs.len()) && (addrs.len() == MAX_ETH_LINKS) {
-					addrs[MAX_ETH_LINKS - 1] = addr.clone();
-				} else {
-					addrs[index] = addr.clone();
-				}
-			});
+			let hash = util_eth::eth_data_hash(bytes);
 
-			Self::deposit_event(Event::EthAddressLinked(account, addr.to_vec()));
+			let displaced = Self::do_link_eth(
+				account,
+				mode,
+				addr_expected,
+				hash,
+				sig,
+				LinkProof::PersonalSign,
+			)?;
 
-			Ok(().into())
+			Ok(Some(T::WeightInfo::link_eth(displaced)).into())
 		}
 
-		/// Link a BTC address to a Litentry account providing a proof signature from the private key
-		/// of that BTC address. The BTC address may either be a legacy P2PK one (started with b'1')
-		/// or a Segwit P2PK one (started with b'bc').
+		/// Link an Ethereum address to the origin Litentry account providing an EIP-712 typed data
+		/// signature from the private key of that Ethereum address.
 		///
-		/// The runtime needs to ensure that a malicious index can be handled correctly.
-		/// Currently, when vec.len > MAX_ETH_LINKS, replacement will always happen at the final index.
-		/// Otherwise it will use the next new slot unless index is valid against a currently available slot.
+		/// Unlike `link_eth`, whose signed message is shown to the user as opaque hex, wallets
+		/// render the `Link(bytes account,uint256 expiringBlockNumber,uint256 nonce)` message in a
+		/// readable form.
+		/// The message is signed under the
+		/// `EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)` domain, where
+		/// `name` is the pallet name, `version` is `EIP712_DOMAIN_VERSION`, `chainId` is
+		/// `Config::EthChainId` and `salt` is the genesis hash.
+		///
+		/// The slot replacement rules are the same as in `link_eth`.
 		///
 		/// Parameters:
-		/// - `account`: The Litentry address that is to be linked
-		/// - `index`: The index of the linked BTC address that the user wants to replace with.
-		/// - `addr_expected`: The intended BTC address to link to the origin's Litentry address
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `addr_expected`: The intended Ethereum address to link to the origin's Litentry address
 		/// - `expiring_block_number`: The block number after which this link request will expire
-		/// - `sig`: The rsv-signature generated by the private key of the addr_expected
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The rsv-signature of the typed data generated by the private key of the addr_expected
 		///
-		/// Emits `BtcAddressLinked` event when successful.
-		#[pallet::weight(T::WeightInfo::link_btc())]
-		pub fn link_btc(
+		/// Emits `EthAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::link_eth_eip712(T::MaxDisplacedAccounts::get()))]
+		pub fn link_eth_eip712(
 			origin: OriginFor<T>,
-			account: T::AccountId,
-			index: u32,
-			addr_expected: Vec<u8>,
+			mode: LinkMode,
+			addr_expected: EthAddress,
 			expiring_block_number: T::BlockNumber,
+			nonce: u32,
 			sig: Signature,
 		) -> DispatchResultWithPostInfo {
-			let _ = ensure_signed(origin)?;
+			let account = ensure_signed(origin)?;
 
-			let current_block_number = <frame_system::Pallet<T>>::block_number();
-			ensure!(expiring_block_number > current_block_number, Error::<T>::LinkRequestExpired);
-			ensure!(
-				(expiring_block_number - current_block_number) <
-					T::BlockNumber::from(EXPIRING_BLOCK_NUMBER_MAX),
-				Error::<T>::InvalidExpiringBlockNumber
-			);
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
 
-			// TODO: we may enlarge this 2
-			if addr_expected.len() < 2 {
-				Err(Error::<T>::InvalidBTCAddress)?
-			}
+			let hash = Self::eip712_link_hash(&account, expiring_block_number, nonce);
 
-			let addr_type = if addr_expected[0] == b'1' {
-				BTCAddrType::Legacy
-			} else if addr_expected[0] == b'b' && addr_expected[1] == b'c' {
-				// TODO: a better way?
-				BTCAddrType::Segwit
-			} else {
-				Err(Error::<T>::InvalidBTCAddress)?
-			};
+			let displaced =
+				Self::do_link_eth(account, mode, addr_expected, hash, sig, LinkProof::Eip712)?;
 
-			let bytes = Self::generate_raw_message(&account, expiring_block_number);
+			Ok(Some(T::WeightInfo::link_eth_eip712(displaced)).into())
+		}
 
-			// TODO: seems btc uses sha256???
-			let hash = sp_io::hashing::keccak_256(&bytes);
+		/// Request to link a smart contract wallet (Gnosis Safe, Argent...) to the origin Litentry
+		/// account providing an EIP-1271 contract signature.
+		///
+		/// The contract can't be queried on chain, so the request stays pending until
+		/// `ContractLinkQuorum` offchain workers attest that its `isValidSignature` method accepts
+		/// the signature of the `link_eth` message hash, see `attest_eth_contract_link`. The link
+		/// is then made following the same slot replacement rules as in `link_eth`.
+		///
+		/// A new request for the same contract replaces the previous one and its attestations.
+		///
+		/// Parameters:
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `addr_expected`: The Ethereum contract address to link to the origin's Litentry address
+		/// - `expiring_block_number`: The block number from which the request can no longer be
+		///   attested
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The contract signature of the message, in whatever format the contract expects
+		///
+		/// Emits `EthContractLinkRequested` event when successful.
+		#[pallet::weight(T::WeightInfo::link_eth_contract())]
+		pub fn link_eth_contract(
+			origin: OriginFor<T>,
+			mode: LinkMode,
+			addr_expected: EthAddress,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
 
-			let mut msg = [0u8; 32];
-			msg[..32].copy_from_slice(&hash[..32]);
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
+			let signature: BoundedVec<u8, T::MaxContractSignatureLen> =
+				sig.try_into().map_err(|_| Error::<T>::ContractSignatureTooLong)?;
 
-			let pk = secp256k1_ecdsa_recover_compressed(&sig, &msg)
-				.map_err(|_| Error::<T>::EcdsaRecoverFailure)?;
+			let bytes = Self::generate_raw_message(&account, expiring_block_number, nonce);
+			let hash = util_eth::eth_data_hash(bytes);
 
-			let addr = match addr_type {
-				BTCAddrType::Legacy => btc::legacy::btc_addr_from_pk(&pk).to_base58(),
-				// Native P2WPKH is a scriptPubKey of 22 bytes.
-				// It starts with a OP_0, followed by a canonical push of the keyhash (i.e. 0x0014{20-byte keyhash})
-				// keyhash is RIPEMD160(SHA256) of a compressed public key
-				// https://bitcoincore.org/en/segwit_wallet_dev/
-				BTCAddrType::Segwit => {
-					let pk_hash = btc::legacy::hash160(&pk);
-					let mut pk = [0u8; 22];
-					pk[0] = 0;
-					pk[1] = 20;
-					pk[2..].copy_from_slice(&pk_hash);
-					let wp = WitnessProgram::from_scriptpubkey(&pk.to_vec())
-						.map_err(|_| Error::<T>::InvalidBTCAddress)?;
-					wp.to_address(b"bc".to_vec()).map_err(|_| Error::<T>::InvalidBTCAddress)?
+			match Self::eth_contract_pending(&account, &addr_expected) {
+				// the deposit of the replaced request is kept
+				Some(request) => EthContractPendingExpiry::<T>::remove(
+					request.expiring_block_number,
+					(&account, &addr_expected),
+				),
+				None => Self::hold_deposit(&account, T::PendingDeposit::get())?,
+			}
+			EthContractPendingExpiry::<T>::insert(
+				expiring_block_number,
+				(&account, &addr_expected),
+				(),
+			);
+			EthContractPending::<T>::insert(
+				&account,
+				&addr_expected,
+				EthContractLinkRequest {
+					mode,
+					expiring_block_number,
+					hash,
+					signature,
+					approvals: Default::default(),
+					rejections: Default::default(),
 				},
-			};
-
-			ensure!(addr == addr_expected, Error::<T>::UnexpectedAddress);
-
-			BitcoinLink::<T>::mutate(&account, |addrs| {
-				let index = index as usize;
-				// NOTE: allow linking `MAX_BTC_LINKS` btc addresses.
-				if (index >= addrs.len()) && (addrs.len() != MAX_BTC_LINKS) {
-					addrs.push(addr.clone());
-				} else if (index >= addrs.len()) && (addrs.len() == MAX_BTC_LINKS) {
-					addrs[MAX_BTC_LINKS - 1] = addr.clone();
-				} else {
-					addrs[index] = addr.clone();
-				}
-			});
+			);
+			Self::increment_link_nonce(&account);
 
-			Self::deposit_event(Event::BtcAddressLinked(account, addr));
+			Self::deposit_event(Event::EthContractLinkRequested(account, addr_expected.to_vec()));
 
 			Ok(().into())
 		}
 
-		/// Initiate a link request to link a Litentry address (= any account in Polkadot ecosystem)
-		/// to another Litentry address (= any account in Polkadot ecosystem).
+		/// Cancel a pending `link_eth_contract` request of the origin Litentry account.
 		///
 		/// Parameters:
-		/// - `account`: The Litentry address that is to be linked
-		/// - `index`: The index of the linked Litentry address that the user wants to replace with.
-		#[pallet::weight(T::WeightInfo::link_polkadot())]
-		pub fn link_polkadot(
+		/// - `addr`: The Ethereum contract address of the request
+		///
+		/// Emits `EthContractLinkCancelled` event when successful.
+		#[pallet::weight(T::WeightInfo::cancel_eth_contract_link())]
+		pub fn cancel_eth_contract_link(
 			origin: OriginFor<T>,
-			account: T::AccountId,
-			index: u32,
+			addr: EthAddress,
 		) -> DispatchResultWithPostInfo {
-			let origin = ensure_signed(origin)?;
+			let account = ensure_signed(origin)?;
 
-			// TODO: charge some fee
+			let request = Self::eth_contract_pending(&account, &addr)
+				.ok_or(Error::<T>::WrongPendingRequest)?;
+			Self::remove_eth_contract_pending(&account, &addr, request.expiring_block_number);
 
-			<PolkadotPending<T>>::insert(origin, (account, index));
+			Self::deposit_event(Event::EthContractLinkCancelled(account, addr.to_vec()));
 
 			Ok(().into())
 		}
 
-		/// Accept a pending `link_polkadot` request to link a Litentry address (= any account in Polkadot ecosystem)
-		/// to another Litentry address (= any account in Polkadot ecosystem).
+		/// Link a BTC address to a Litentry account providing a proof signature from the private key
+		/// of that BTC address. The BTC address may either be a legacy P2PK one (started with b'1'),
+		/// a P2SH wrapped Segwit one (started with b'3'), a Segwit P2PK one (started with b'bc1q')
+		/// or a Taproot one (started with b'bc1p'). These are the mainnet prefixes, the accepted
+		/// network is configured by `Config::BitcoinNetwork`.
 		///
-		/// The runtime needs to ensure that a malicious index can be handled correctly.
-		/// Currently, when vec.len > MAX_ETH_LINKS, replacement will always happen at the final index.
-		/// Otherwise it will use the next new slot unless index is valid against a currently available slot.
+		/// `LinkMode::Append` fails with `LinkSlotsFull` once all the slots are taken, an address
+		/// linked before is only ever overwritten by an explicit `LinkMode::Replace`.
 		///
 		/// Parameters:
-		/// - `account`: The Litentry address that is to be linked
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `addr_expected`: The intended BTC address to link to the origin's Litentry address
+		/// - `expiring_block_number`: The block number after which this link request will expire
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The signature generated by the private key of the addr_expected over the message
+		///   assembled by `generate_text_message`. For Taproot addresses it's a BIP-322 simple
+		///   signature (the encoded witness stack), otherwise a BIP-137 signature (header byte
+		///   followed by r and s)
 		///
-		/// Emits `PolkadotAddressLinked` event when successful.
-		#[pallet::weight(T::WeightInfo::accept_polkadot())]
-		pub fn accept_polkadot(
+		/// Emits `BtcAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::link_btc(T::MaxDisplacedAccounts::get()))]
+		pub fn link_btc(
 			origin: OriginFor<T>,
-			account: T::AccountId,
+			mode: LinkMode,
+			addr_expected: Vec<u8>,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: Vec<u8>,
 		) -> DispatchResultWithPostInfo {
-			let origin = ensure_signed(origin)?;
+			let account = ensure_signed(origin)?;
 
-			let (target, index) = Self::polkadot_pending(&account);
-			ensure!(target == origin, Error::<T>::WrongPendingRequest);
-			<PolkadotPending<T>>::remove(&origin);
-
-			PolkadotLink::<T>::mutate(&account, |addrs| {
-				let index = index as usize;
-				// NOTE: allow linking `MAX_POLKADOT_LINKS` polkadot addresses.
-				if (index >= addrs.len()) && (addrs.len() != MAX_POLKADOT_LINKS) {
-					addrs.push(origin.clone());
-				} else if (index >= addrs.len()) && (addrs.len() == MAX_POLKADOT_LINKS) {
-					addrs[MAX_POLKADOT_LINKS - 1] = origin.clone();
-				} else {
-					addrs[index] = origin.clone();
-				}
-			});
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
 
-			Self::deposit_event(Event::PolkadotAddressLinked(account, origin));
+			let bytes = Self::generate_text_message(&account, expiring_block_number, nonce);
+			let (addr, proof) = Self::recover_btc_address(&addr_expected, &bytes, &sig)?;
 
-			Ok(().into())
+			ensure!(addr == addr_expected, Error::<T>::UnexpectedAddress);
+			let displaced = Self::displaced_accounts(&account, || {
+				BitcoinLinkAccounts::<T>::iter_prefix(&addr)
+			})?;
+
+			// NOTE: allow linking `MaxBtcLinks` btc addresses.
+			let replaced = BitcoinLink::<T>::try_mutate(&account, |links| {
+				Self::insert_link(
+					links,
+					&account,
+					mode,
+					addr.clone(),
+					proof,
+					LinkedAddress::Bitcoin,
+				)
+			})?;
+			if let Some(replaced) = replaced {
+				BitcoinLinkAccounts::<T>::remove(&replaced, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Bitcoin(replaced));
+			}
+			BitcoinLinkAccounts::<T>::insert(&addr, &account, ());
+			Self::increment_link_nonce(&account);
+
+			let weight = T::WeightInfo::link_btc(displaced.len() as u32);
+			for other in displaced {
+				BitcoinLink::<T>::mutate_exists(&other, |links| {
+					Self::evict_link(links, &other, &addr)
+				});
+				BitcoinLinkAccounts::<T>::remove(&addr, &other);
+				T::OnLinkChanged::on_unlinked(&other, &LinkedAddress::Bitcoin(addr.clone()));
+				Self::deposit_event(Event::BtcAddressTransferred(
+					other,
+					account.clone(),
+					addr.clone(),
+				));
+			}
+
+			T::OnLinkChanged::on_linked(&account, &LinkedAddress::Bitcoin(addr.clone()));
+			Self::deposit_event(Event::BtcAddressLinked(account, addr));
+
+			Ok(Some(weight).into())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		/// Assemble the message that the user has signed
-		/// Format: "Link Litentry: " + Litentry account + expiring block number
-		fn generate_raw_message(
-			account: &T::AccountId,
+		/// Link a Tron address to the origin Litentry account providing a proof signature from the
+		/// private key of that Tron address.
+		///
+		/// Tron keys are Ethereum keys, the address is the same 20 bytes as the Ethereum address
+		/// and the message is signed the same way as in `link_eth`, under the
+		/// `"\x19TRON Signed Message:\n"` prefix of TronWeb's `signMessageV2`.
+		///
+		/// The slot replacement rules are the same as in `link_eth`.
+		///
+		/// Parameters:
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `addr_expected`: The intended Tron address (the 20 bytes without the 0x41 version
+		///   byte) to link to the origin's Litentry address
+		/// - `expiring_block_number`: The block number after which this link request will expire
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The rsv-signature generated by the private key of the addr_expected
+		///
+		/// Emits `TronAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::link_tron(T::MaxDisplacedAccounts::get()))]
+		pub fn link_tron(
+			origin: OriginFor<T>,
+			mode: LinkMode,
+			addr_expected: EthAddress,
 			expiring_block_number: T::BlockNumber,
-		) -> Vec<u8> {
-			let mut bytes = b"Link Litentry: ".encode();
-			let mut account_vec = account.encode();
-			let mut expiring_block_number_vec = expiring_block_number.encode();
+			nonce: u32,
+			sig: Signature,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
 
-			bytes.append(&mut account_vec);
-			bytes.append(&mut expiring_block_number_vec);
-			bytes
-		}
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
+
+			let bytes = Self::generate_raw_message(&account, expiring_block_number, nonce);
+
+			let hash = util_eth::signed_data_hash(util_eth::TRON_MESSAGE_PREFIX, bytes);
+
+			let addr =
+				util_eth::addr_from_sig(hash, sig).map_err(|_| Error::<T>::EcdsaRecoverFailure)?;
+			ensure!(addr == addr_expected, Error::<T>::UnexpectedAddress);
+			let displaced =
+				Self::displaced_accounts(&account, || TronLinkAccounts::<T>::iter_prefix(&addr))?;
+
+			// NOTE: allow linking `MaxTronLinks` tron addresses.
+			let replaced = TronLink::<T>::try_mutate(&account, |links| {
+				Self::insert_link(
+					links,
+					&account,
+					mode,
+					addr,
+					LinkProof::Tip191,
+					LinkedAddress::Tron,
+				)
+			})?;
+			if let Some(replaced) = replaced {
+				TronLinkAccounts::<T>::remove(&replaced, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Tron(replaced));
+			}
+			TronLinkAccounts::<T>::insert(&addr, &account, ());
+			Self::increment_link_nonce(&account);
+
+			let weight = T::WeightInfo::link_tron(displaced.len() as u32);
+			for other in displaced {
+				TronLink::<T>::mutate_exists(&other, |links| {
+					Self::evict_link(links, &other, &addr)
+				});
+				TronLinkAccounts::<T>::remove(&addr, &other);
+				T::OnLinkChanged::on_unlinked(&other, &LinkedAddress::Tron(addr));
+				Self::deposit_event(Event::TronAddressTransferred(
+					other,
+					account.clone(),
+					util_eth::tron_address(&addr),
+				));
+			}
+
+			T::OnLinkChanged::on_linked(&account, &LinkedAddress::Tron(addr));
+			Self::deposit_event(Event::TronAddressLinked(account, util_eth::tron_address(&addr)));
+
+			Ok(Some(weight).into())
+		}
+
+		/// Link a Solana address to a Litentry account providing an ed25519 signature from the
+		/// private key of that Solana address.
+		///
+		/// The slot replacement rules are the same as in `link_btc`.
+		///
+		/// Parameters:
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `addr_expected`: The intended Solana address (the raw ed25519 public key) to link to
+		///   the origin's Litentry address
+		/// - `expiring_block_number`: The block number after which this link request will expire
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The ed25519 signature generated by the private key of the addr_expected over
+		///   the message assembled by `generate_text_message`
+		///
+		/// Emits `SolanaAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::link_solana(T::MaxDisplacedAccounts::get()))]
+		pub fn link_solana(
+			origin: OriginFor<T>,
+			mode: LinkMode,
+			addr_expected: SolanaAddress,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: [u8; 64],
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
+
+			let bytes = Self::generate_text_message(&account, expiring_block_number, nonce);
+			ensure!(
+				ed25519_verify(
+					&ed25519::Signature::from_raw(sig),
+					&bytes,
+					&ed25519::Public::from_raw(addr_expected)
+				),
+				Error::<T>::InvalidSolanaSignature
+			);
+
+			let addr = addr_expected;
+			let displaced =
+				Self::displaced_accounts(&account, || SolanaLinkAccounts::<T>::iter_prefix(&addr))?;
+
+			// NOTE: allow linking `MaxSolanaLinks` solana addresses.
+			let replaced = SolanaLink::<T>::try_mutate(&account, |links| {
+				Self::insert_link(
+					links,
+					&account,
+					mode,
+					addr,
+					LinkProof::Ed25519,
+					LinkedAddress::Solana,
+				)
+			})?;
+			if let Some(replaced) = replaced {
+				SolanaLinkAccounts::<T>::remove(&replaced, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Solana(replaced));
+			}
+			SolanaLinkAccounts::<T>::insert(&addr, &account, ());
+			Self::increment_link_nonce(&account);
+
+			let weight = T::WeightInfo::link_solana(displaced.len() as u32);
+			for other in displaced {
+				SolanaLink::<T>::mutate_exists(&other, |links| {
+					Self::evict_link(links, &other, &addr)
+				});
+				SolanaLinkAccounts::<T>::remove(&addr, &other);
+				T::OnLinkChanged::on_unlinked(&other, &LinkedAddress::Solana(addr));
+				Self::deposit_event(Event::SolanaAddressTransferred(
+					other,
+					account.clone(),
+					addr.to_base58(),
+				));
+			}
+
+			T::OnLinkChanged::on_linked(&account, &LinkedAddress::Solana(addr));
+			Self::deposit_event(Event::SolanaAddressLinked(account, addr.to_base58()));
+
+			Ok(Some(weight).into())
+		}
+
+		/// Link a Cosmos SDK address to a Litentry account providing an ADR-036 signature from
+		/// the private key of that Cosmos SDK address, as produced by Keplr's `signArbitrary`.
+		///
+		/// The signed data is the message assembled by `generate_text_message`, wrapped by the
+		/// wallet into the `sign/MsgSignData` amino sign document of `addr_expected`. Only
+		/// secp256k1 addresses with the `Config::CosmosHrp` human readable part are accepted.
+		///
+		/// The slot replacement rules are the same as in `link_btc`.
+		///
+		/// Parameters:
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `addr_expected`: The intended bech32 Cosmos SDK address to link to the origin's
+		///   Litentry address
+		/// - `expiring_block_number`: The block number after which this link request will expire
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The 64 bytes (r and s) secp256k1 signature of the sign document
+		///
+		/// Emits `CosmosAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::link_cosmos(T::MaxDisplacedAccounts::get()))]
+		pub fn link_cosmos(
+			origin: OriginFor<T>,
+			mode: LinkMode,
+			addr_expected: Vec<u8>,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: [u8; 64],
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
+
+			let mut hrp = T::CosmosHrp::get();
+			hrp.push(b'1');
+			ensure!(addr_expected.starts_with(&hrp), Error::<T>::InvalidCosmosAddress);
+
+			let bytes = Self::generate_text_message(&account, expiring_block_number, nonce);
+			let addr = Self::recover_adr036_address(&addr_expected, &bytes, sig)?;
+
+			ensure!(addr == addr_expected, Error::<T>::UnexpectedAddress);
+			let displaced =
+				Self::displaced_accounts(&account, || CosmosLinkAccounts::<T>::iter_prefix(&addr))?;
+
+			// NOTE: allow linking `MaxCosmosLinks` cosmos addresses.
+			let replaced = CosmosLink::<T>::try_mutate(&account, |links| {
+				Self::insert_link(
+					links,
+					&account,
+					mode,
+					addr.clone(),
+					LinkProof::Adr036,
+					LinkedAddress::Cosmos,
+				)
+			})?;
+			if let Some(replaced) = replaced {
+				CosmosLinkAccounts::<T>::remove(&replaced, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Cosmos(replaced));
+			}
+			CosmosLinkAccounts::<T>::insert(&addr, &account, ());
+			Self::increment_link_nonce(&account);
+
+			let weight = T::WeightInfo::link_cosmos(displaced.len() as u32);
+			for other in displaced {
+				CosmosLink::<T>::mutate_exists(&other, |links| {
+					Self::evict_link(links, &other, &addr)
+				});
+				CosmosLinkAccounts::<T>::remove(&addr, &other);
+				T::OnLinkChanged::on_unlinked(&other, &LinkedAddress::Cosmos(addr.clone()));
+				Self::deposit_event(Event::CosmosAddressTransferred(
+					other,
+					account.clone(),
+					addr.clone(),
+				));
+			}
+
+			T::OnLinkChanged::on_linked(&account, &LinkedAddress::Cosmos(addr.clone()));
+			Self::deposit_event(Event::CosmosAddressLinked(account, addr));
+
+			Ok(Some(weight).into())
+		}
+
+		/// Initiate a link request to link a Litentry address (= any account in Polkadot ecosystem)
+		/// to another Litentry address (= any account in Polkadot ecosystem).
+		///
+		/// Requests to several target accounts can be pending at the same time, a new request to
+		/// the same target account replaces the previous one.
+		///
+		/// Parameters:
+		/// - `account`: The Litentry address that is to be linked
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `expiring_block_number`: The block number from which the request can no longer be
+		///   accepted, it is removed by `on_initialize` afterwards
+		#[pallet::weight(T::WeightInfo::link_polkadot())]
+		pub fn link_polkadot(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			mode: LinkMode,
+			expiring_block_number: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+			ensure!(account != origin, Error::<T>::LinkToSelf);
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+
+			match Self::polkadot_pending(&origin, &account) {
+				// the deposit of the replaced request is kept
+				Some(request) => PolkadotPendingExpiry::<T>::remove(
+					request.expiring_block_number,
+					(&origin, &account),
+				),
+				None => Self::hold_deposit(&origin, T::PendingDeposit::get())?,
+			}
+			PolkadotPendingExpiry::<T>::insert(expiring_block_number, (&origin, &account), ());
+			<PolkadotPending<T>>::insert(
+				&origin,
+				&account,
+				PolkadotLinkRequest { mode, expiring_block_number },
+			);
+
+			Ok(().into())
+		}
+
+		/// Accept a pending `link_polkadot` request to link a Litentry address (= any account in Polkadot ecosystem)
+		/// to another Litentry address (= any account in Polkadot ecosystem).
+		///
+		/// `LinkMode::Append` fails with `LinkSlotsFull` once all the slots are taken, an address
+		/// linked before is only ever overwritten by an explicit `LinkMode::Replace`.
+		///
+		/// Parameters:
+		/// - `account`: The Litentry address that is to be linked
+		///
+		/// Emits `PolkadotAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::accept_polkadot())]
+		pub fn accept_polkadot(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+
+			let request =
+				Self::polkadot_pending(&account, &origin).ok_or(Error::<T>::WrongPendingRequest)?;
+			ensure!(
+				request.expiring_block_number > <frame_system::Pallet<T>>::block_number(),
+				Error::<T>::LinkRequestExpired
+			);
+
+			Self::do_link_polkadot(&account, &origin, request.mode, LinkProof::Accepted)?;
+			// the request is kept when the link fails, so it can be accepted once a slot is free
+			Self::remove_polkadot_pending(&account, &origin, request.expiring_block_number);
+
+			Self::deposit_event(Event::PolkadotAddressLinked(account, origin));
+
+			Ok(().into())
+		}
+
+		/// Cancel a pending `link_polkadot` request of the origin Litentry account.
+		///
+		/// Parameters:
+		/// - `account`: The target Litentry address of the request
+		///
+		/// Emits `PolkadotLinkCancelled` event when successful.
+		#[pallet::weight(T::WeightInfo::cancel_polkadot_link())]
+		pub fn cancel_polkadot_link(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+
+			let request =
+				Self::polkadot_pending(&origin, &account).ok_or(Error::<T>::WrongPendingRequest)?;
+			Self::remove_polkadot_pending(&origin, &account, request.expiring_block_number);
+
+			Self::deposit_event(Event::PolkadotLinkCancelled(origin, account));
+
+			Ok(().into())
+		}
+
+		/// Link a Litentry address (= any account in Polkadot ecosystem) to the origin Litentry
+		/// account in a single transaction, providing a signature from that Litentry address in place
+		/// of `link_polkadot` and `accept_polkadot`.
+		///
+		/// The signed message is the same as the one of `link_eth`, see `generate_raw_message`. It
+		/// can also be signed wrapped in `<Bytes>` and `</Bytes>`, as wallets do for raw payloads.
+		///
+		/// Parameters:
+		/// - `account`: The Litentry address that is to be linked
+		/// - `mode`: Whether to append the address or replace the one linked at a given slot
+		/// - `expiring_block_number`: The block number after which this link request will expire
+		/// - `nonce`: The current link nonce of the origin Litentry account, see `link_nonce`
+		/// - `sig`: The signature generated by the private key of `account`
+		///
+		/// Emits `PolkadotAddressLinked` event when successful.
+		#[pallet::weight(T::WeightInfo::link_polkadot_signed())]
+		pub fn link_polkadot_signed(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			mode: LinkMode,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: T::PolkadotSignature,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+			ensure!(account != origin, Error::<T>::LinkToSelf);
+
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&origin, nonce)?;
+
+			let bytes = Self::generate_raw_message(&origin, expiring_block_number, nonce);
+			ensure!(
+				Self::verify_polkadot_signature(&sig, &bytes, &account),
+				Error::<T>::InvalidPolkadotSignature
+			);
+
+			Self::do_link_polkadot(&origin, &account, mode, LinkProof::MultiSignature)?;
+			Self::increment_link_nonce(&origin);
+			// a pending request to the same account has no use anymore
+			if let Some(request) = Self::polkadot_pending(&origin, &account) {
+				Self::remove_polkadot_pending(&origin, &account, request.expiring_block_number);
+			}
+
+			Self::deposit_event(Event::PolkadotAddressLinked(origin, account));
+
+			Ok(().into())
+		}
+
+		/// Unlink an Ethereum address from the origin Litentry account.
+		///
+		/// Parameters:
+		/// - `addr`: The linked Ethereum address to be removed
+		///
+		/// Emits `EthAddressUnlinked` event once the last slot of the address is removed.
+		#[pallet::weight(T::WeightInfo::unlink_eth())]
+		pub fn unlink_eth(origin: OriginFor<T>, addr: EthAddress) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			let unlinked = EthereumLink::<T>::try_mutate_exists(&account, |links| {
+				Self::remove_link(links, &account, &addr)
+			})?;
+			if unlinked {
+				EthereumLinkAccounts::<T>::remove(&addr, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Ethereum(addr));
+				Self::deposit_event(Event::EthAddressUnlinked(account, addr.to_vec()));
+			}
+
+			Ok(().into())
+		}
+
+		/// Unlink a BTC address from the origin Litentry account.
+		///
+		/// Parameters:
+		/// - `addr`: The linked BTC address to be removed
+		///
+		/// Emits `BtcAddressUnlinked` event once the last slot of the address is removed.
+		#[pallet::weight(T::WeightInfo::unlink_btc())]
+		pub fn unlink_btc(origin: OriginFor<T>, addr: Vec<u8>) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			let unlinked = BitcoinLink::<T>::try_mutate_exists(&account, |links| {
+				Self::remove_link(links, &account, &addr)
+			})?;
+			if unlinked {
+				BitcoinLinkAccounts::<T>::remove(&addr, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Bitcoin(addr.clone()));
+				Self::deposit_event(Event::BtcAddressUnlinked(account, addr));
+			}
+
+			Ok(().into())
+		}
+
+		/// Unlink a Tron address from the origin Litentry account.
+		///
+		/// Parameters:
+		/// - `addr`: The linked Tron address (the 20 bytes without the 0x41 version byte) to be
+		///   removed
+		///
+		/// Emits `TronAddressUnlinked` event once the last slot of the address is removed.
+		#[pallet::weight(T::WeightInfo::unlink_tron())]
+		pub fn unlink_tron(origin: OriginFor<T>, addr: EthAddress) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			let unlinked = TronLink::<T>::try_mutate_exists(&account, |links| {
+				Self::remove_link(links, &account, &addr)
+			})?;
+			if unlinked {
+				TronLinkAccounts::<T>::remove(&addr, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Tron(addr));
+				Self::deposit_event(Event::TronAddressUnlinked(
+					account,
+					util_eth::tron_address(&addr),
+				));
+			}
+
+			Ok(().into())
+		}
+
+		/// Unlink a Solana address from the origin Litentry account.
+		///
+		/// Parameters:
+		/// - `addr`: The linked Solana address (the raw ed25519 public key) to be removed
+		///
+		/// Emits `SolanaAddressUnlinked` event once the last slot of the address is removed.
+		#[pallet::weight(T::WeightInfo::unlink_solana())]
+		pub fn unlink_solana(
+			origin: OriginFor<T>,
+			addr: SolanaAddress,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			let unlinked = SolanaLink::<T>::try_mutate_exists(&account, |links| {
+				Self::remove_link(links, &account, &addr)
+			})?;
+			if unlinked {
+				SolanaLinkAccounts::<T>::remove(&addr, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Solana(addr));
+				Self::deposit_event(Event::SolanaAddressUnlinked(account, addr.to_base58()));
+			}
+
+			Ok(().into())
+		}
+
+		/// Unlink a Cosmos SDK address from the origin Litentry account.
+		///
+		/// Parameters:
+		/// - `addr`: The linked bech32 Cosmos SDK address to be removed
+		///
+		/// Emits `CosmosAddressUnlinked` event once the last slot of the address is removed.
+		#[pallet::weight(T::WeightInfo::unlink_cosmos())]
+		pub fn unlink_cosmos(origin: OriginFor<T>, addr: Vec<u8>) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			let unlinked = CosmosLink::<T>::try_mutate_exists(&account, |links| {
+				Self::remove_link(links, &account, &addr)
+			})?;
+			if unlinked {
+				CosmosLinkAccounts::<T>::remove(&addr, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Cosmos(addr.clone()));
+				Self::deposit_event(Event::CosmosAddressUnlinked(account, addr));
+			}
+
+			Ok(().into())
+		}
+
+		/// Unlink a Litentry address (= any account in Polkadot ecosystem) from the origin
+		/// Litentry account.
+		///
+		/// Parameters:
+		/// - `addr`: The linked Litentry address to be removed
+		///
+		/// Emits `PolkadotAddressUnlinked` event once the last slot of the address is removed.
+		#[pallet::weight(T::WeightInfo::unlink_polkadot())]
+		pub fn unlink_polkadot(
+			origin: OriginFor<T>,
+			addr: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			let unlinked = PolkadotLink::<T>::try_mutate_exists(&account, |links| {
+				Self::remove_link(links, &account, &addr)
+			})?;
+			if unlinked {
+				PolkadotLinkAccounts::<T>::remove(&addr, &account);
+				T::OnLinkChanged::on_unlinked(&account, &LinkedAddress::Polkadot(addr.clone()));
+				Self::deposit_event(Event::PolkadotAddressUnlinked(account, addr));
+			}
+
+			Ok(().into())
+		}
+
+		/// Set or clear the label of an address linked to the origin Litentry account, e.g. the
+		/// name of the wallet holding it.
+		///
+		/// Parameters:
+		/// - `addr`: The linked address, labelled at every slot it is linked at
+		/// - `label`: The new label, at most `MaxLinkLabelLen` bytes, or `None` to clear it
+		///
+		/// Emits `LinkLabelSet` event when successful.
+		#[pallet::weight(T::WeightInfo::set_link_label())]
+		pub fn set_link_label(
+			origin: OriginFor<T>,
+			addr: LinkedAddress<T::AccountId>,
+			label: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			ensure!(
+				label.as_ref().map_or(true, |l| l.len() <= T::MaxLinkLabelLen::get() as usize),
+				Error::<T>::LinkLabelTooLong
+			);
+
+			match &addr {
+				LinkedAddress::Ethereum(a) =>
+					EthereumLink::<T>::try_mutate_exists(&account, |links| {
+						Self::update_links(links, a, |link| link.label = label.clone())
+					}),
+				LinkedAddress::Bitcoin(a) =>
+					BitcoinLink::<T>::try_mutate_exists(&account, |links| {
+						Self::update_links(links, a, |link| link.label = label.clone())
+					}),
+				LinkedAddress::Tron(a) => TronLink::<T>::try_mutate_exists(&account, |links| {
+					Self::update_links(links, a, |link| link.label = label.clone())
+				}),
+				LinkedAddress::Solana(a) => SolanaLink::<T>::try_mutate_exists(&account, |links| {
+					Self::update_links(links, a, |link| link.label = label.clone())
+				}),
+				LinkedAddress::Cosmos(a) => CosmosLink::<T>::try_mutate_exists(&account, |links| {
+					Self::update_links(links, a, |link| link.label = label.clone())
+				}),
+				LinkedAddress::Polkadot(a) =>
+					PolkadotLink::<T>::try_mutate_exists(&account, |links| {
+						Self::update_links(links, a, |link| link.label = label.clone())
+					}),
+			}?;
+
+			Self::deposit_event(Event::LinkLabelSet(account, addr));
+
+			Ok(().into())
+		}
+
+		/// Prove the ownership of an address linked to the origin Litentry account again, so that
+		/// the link stays fresh for another `LinkValidityPeriod` blocks.
+		///
+		/// The signed message and the signature format are the ones of the link call of the
+		/// address network: `link_eth`, `link_btc`, `link_tron`, `link_solana`, `link_cosmos` or
+		/// `link_polkadot_signed`. Smart contract wallets can't sign the `link_eth` message, they
+		/// are refreshed by linking them again with `link_eth_contract` and `LinkMode::Replace`.
+		/// The link keeps the proof it was made with, e.g. `LinkProof::Eip712`.
+		///
+		/// Each network has its own weight, the one of its most expensive signature scheme.
+		///
+		/// Parameters:
+		/// - `addr`: The linked address, refreshed at every slot it is linked at
+		/// - `expiring_block_number`: The block number after which this refresh request will expire
+		/// - `nonce`: The current link nonce of the Litentry account, see `link_nonce`
+		/// - `sig`: The signature generated by the private key of `addr`, e.g. the 65 bytes
+		///   rsv-signature for an Ethereum address or the SCALE encoded `PolkadotSignature` for a
+		///   Litentry address
+		///
+		/// Emits `LinkRefreshed` event when successful.
+		#[pallet::weight(match addr {
+			LinkedAddress::Ethereum(_) => T::WeightInfo::refresh_link_eth(),
+			LinkedAddress::Bitcoin(_) => T::WeightInfo::refresh_link_btc(),
+			LinkedAddress::Tron(_) => T::WeightInfo::refresh_link_tron(),
+			LinkedAddress::Solana(_) => T::WeightInfo::refresh_link_solana(),
+			LinkedAddress::Cosmos(_) => T::WeightInfo::refresh_link_cosmos(),
+			LinkedAddress::Polkadot(_) => T::WeightInfo::refresh_link_polkadot(),
+		})]
+		pub fn refresh_link(
+			origin: OriginFor<T>,
+			addr: LinkedAddress<T::AccountId>,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+
+			Self::ensure_valid_expiring_block_number(expiring_block_number)?;
+			Self::ensure_valid_link_nonce(&account, nonce)?;
+
+			Self::verify_refresh_signature(&account, &addr, expiring_block_number, nonce, &sig)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			match &addr {
+				LinkedAddress::Ethereum(a) =>
+					EthereumLink::<T>::try_mutate_exists(&account, |links| {
+						Self::update_links(links, a, |link| link.verified_at = now)
+					}),
+				LinkedAddress::Bitcoin(a) =>
+					BitcoinLink::<T>::try_mutate_exists(&account, |links| {
+						Self::update_links(links, a, |link| link.verified_at = now)
+					}),
+				LinkedAddress::Tron(a) => TronLink::<T>::try_mutate_exists(&account, |links| {
+					Self::update_links(links, a, |link| link.verified_at = now)
+				}),
+				LinkedAddress::Solana(a) => SolanaLink::<T>::try_mutate_exists(&account, |links| {
+					Self::update_links(links, a, |link| link.verified_at = now)
+				}),
+				LinkedAddress::Cosmos(a) => CosmosLink::<T>::try_mutate_exists(&account, |links| {
+					Self::update_links(links, a, |link| link.verified_at = now)
+				}),
+				LinkedAddress::Polkadot(a) =>
+					PolkadotLink::<T>::try_mutate_exists(&account, |links| {
+						Self::update_links(links, a, |link| link.verified_at = now)
+					}),
+			}?;
+			Self::schedule_stale_link(&account, addr.clone(), now);
+			Self::increment_link_nonce(&account);
+
+			Self::deposit_event(Event::LinkRefreshed(account, addr));
+
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Assemble the message that the user has signed
+		/// Format: "Link Litentry: " + link domain + Litentry account + expiring block number + link nonce
+		pub fn generate_raw_message(
+			account: &T::AccountId,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+		) -> Vec<u8> {
+			let mut bytes = LINK_MESSAGE_PREFIX.to_vec();
+			let mut domain_vec = T::LinkDomain::get();
+			let mut account_vec = account.encode();
+			let mut expiring_block_number_vec = expiring_block_number.encode();
+			let mut nonce_vec = nonce.encode();
+
+			bytes.append(&mut domain_vec);
+			bytes.append(&mut account_vec);
+			bytes.append(&mut expiring_block_number_vec);
+			bytes.append(&mut nonce_vec);
+			bytes
+		}
+
+		/// Assemble the text message that the user has signed with a Bitcoin, Solana or Cosmos wallet
+		/// Format: "Link Litentry: " + hex encoded (link domain + Litentry account + expiring block
+		/// number + link nonce)
+		pub fn generate_text_message(
+			account: &T::AccountId,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+		) -> Vec<u8> {
+			let mut payload = T::LinkDomain::get();
+			payload.append(&mut account.encode());
+			payload.append(&mut expiring_block_number.encode());
+			payload.append(&mut nonce.encode());
+
+			let mut bytes = LINK_MESSAGE_PREFIX.to_vec();
+			bytes.append(&mut btc::message::to_hex(&payload));
+			bytes
+		}
+
+		/// The EIP-712 domain of the typed data signed with `link_eth_eip712`, its salt is the
+		/// genesis hash
+		pub fn eip712_domain() -> Eip712Domain {
+			let genesis_hash = <frame_system::Pallet<T>>::block_hash(T::BlockNumber::zero());
+			let genesis_hash = genesis_hash.as_ref();
+			let mut salt = [0u8; 32];
+			let salt_len = genesis_hash.len().min(32);
+			salt[..salt_len].copy_from_slice(&genesis_hash[..salt_len]);
+
+			Eip712Domain {
+				name: <Self as PalletInfoAccess>::name().as_bytes().to_vec(),
+				version: EIP712_DOMAIN_VERSION.to_vec(),
+				chain_id: T::EthChainId::get(),
+				salt,
+			}
+		}
+
+		/// The EIP-712 `hashStruct` of the `Link` message signed with `link_eth_eip712`
+		pub fn eip712_link_struct_hash(
+			account: &T::AccountId,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+		) -> [u8; 32] {
+			util_eth::eip712_link_struct_hash(
+				&account.encode(),
+				expiring_block_number.unique_saturated_into(),
+				nonce,
+			)
+		}
+
+		/// Assemble the EIP-712 typed data hash that the user has signed with `link_eth_eip712`
+		pub fn eip712_link_hash(
+			account: &T::AccountId,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+		) -> [u8; 32] {
+			util_eth::eip712_hash(
+				Self::eip712_domain().separator(),
+				Self::eip712_link_struct_hash(account, expiring_block_number, nonce),
+			)
+		}
+
+		/// Recover the BTC address that signed `msg`, in the format of the type of `addr_expected`,
+		/// together with the scheme of the signature
+		fn recover_btc_address(
+			addr_expected: &[u8],
+			msg: &[u8],
+			sig: &[u8],
+		) -> Result<(Vec<u8>, LinkProof), DispatchError> {
+			// TODO: we may enlarge this 2
+			if addr_expected.len() < 2 {
+				Err(Error::<T>::InvalidBTCAddress)?
+			}
+
+			let network = T::BitcoinNetwork::get();
+			let mut segwit_prefix = network.hrp().to_vec();
+			segwit_prefix.push(b'1');
+
+			let addr_type = if network.p2pkh_prefixes().contains(&addr_expected[0]) {
+				BTCAddrType::Legacy
+			} else if network.p2sh_prefixes().contains(&addr_expected[0]) {
+				BTCAddrType::NestedSegwit
+			} else if addr_expected.starts_with(&segwit_prefix) {
+				// The witness version follows the separator, version 1 is encoded as b'p'
+				if addr_expected.get(segwit_prefix.len()) == Some(&b'p') {
+					BTCAddrType::Taproot
+				} else {
+					BTCAddrType::Segwit
+				}
+			} else {
+				Err(Error::<T>::InvalidBTCAddress)?
+			};
+
+			Ok(match addr_type {
+				BTCAddrType::Taproot => (
+					Self::verify_bip322_taproot(network, addr_expected, msg, sig)?,
+					LinkProof::Bip322,
+				),
+				_ =>
+					(Self::recover_bip137_address(addr_type, network, msg, sig)?, LinkProof::Bip137),
+			})
+		}
+
+		/// Recover the BTC address of the key that produced a BIP-137 signature of `msg`
+		fn recover_bip137_address(
+			addr_type: BTCAddrType,
+			network: BitcoinNetwork,
+			msg: &[u8],
+			sig: &[u8],
+		) -> Result<Vec<u8>, DispatchError> {
+			let mut sig_bytes: Signature = [0u8; 65];
+			ensure!(sig.len() == sig_bytes.len(), Error::<T>::InvalidBTCSignature);
+			sig_bytes.copy_from_slice(sig);
+
+			let (sig_type, sig) = btc::message::parse_signature(&sig_bytes)
+				.map_err(|_| Error::<T>::InvalidBTCSignature)?;
+
+			// Wallets used to sign with the P2PKH compressed header for segwit addresses before
+			// BIP-137 was widespread, so it is still accepted for them.
+			match (&addr_type, sig_type) {
+				(BTCAddrType::Legacy, SignatureType::P2PKHUncompressed) |
+				(BTCAddrType::Legacy, SignatureType::P2PKHCompressed) |
+				(BTCAddrType::NestedSegwit, SignatureType::P2PKHCompressed) |
+				(BTCAddrType::NestedSegwit, SignatureType::P2SHP2WPKH) |
+				(BTCAddrType::Segwit, SignatureType::P2PKHCompressed) |
+				(BTCAddrType::Segwit, SignatureType::P2WPKH) => (),
+				_ => Err(Error::<T>::UnexpectedBTCSignatureType)?,
+			}
+
+			let msg = btc::message::signed_message_hash(msg);
+
+			let pk = if sig_type == SignatureType::P2PKHUncompressed {
+				let pk_raw = secp256k1_ecdsa_recover(&sig, &msg)
+					.map_err(|_| Error::<T>::EcdsaRecoverFailure)?;
+				let mut pk = vec![0x04];
+				pk.extend_from_slice(&pk_raw);
+				pk
+			} else {
+				secp256k1_ecdsa_recover_compressed(&sig, &msg)
+					.map_err(|_| Error::<T>::EcdsaRecoverFailure)?
+					.to_vec()
+			};
+
+			let addr = match addr_type {
+				BTCAddrType::Legacy => btc::legacy::btc_addr_from_pk(&pk, network).to_base58(),
+				BTCAddrType::NestedSegwit =>
+					btc::legacy::btc_p2sh_p2wpkh_addr_from_pk(&pk, network).to_base58(),
+				// Native P2WPKH is a scriptPubKey of 22 bytes.
+				// It starts with a OP_0, followed by a canonical push of the keyhash (i.e. 0x0014{20-byte keyhash})
+				// keyhash is RIPEMD160(SHA256) of a compressed public key
+				// https://bitcoincore.org/en/segwit_wallet_dev/
+				BTCAddrType::Segwit => {
+					let pk_hash = btc::legacy::hash160(&pk);
+					let mut pk = [0u8; 22];
+					pk[0] = 0;
+					pk[1] = 20;
+					pk[2..].copy_from_slice(&pk_hash);
+					let wp = WitnessProgram::from_scriptpubkey(&pk.to_vec())
+						.map_err(|_| Error::<T>::InvalidBTCAddress)?;
+					wp.to_address(network.hrp().to_vec())
+						.map_err(|_| Error::<T>::InvalidBTCAddress)?
+				},
+				BTCAddrType::Taproot => Err(Error::<T>::UnexpectedBTCSignatureType)?,
+			};
+			Ok(addr)
+		}
+
+		/// Verify a BIP-322 simple signature of `msg` for a Taproot address, the signature is the
+		/// consensus encoded witness stack of the BIP-322 `to_sign` transaction
+		fn verify_bip322_taproot(
+			network: BitcoinNetwork,
+			addr: &[u8],
+			msg: &[u8],
+			sig: &[u8],
+		) -> Result<Vec<u8>, DispatchError> {
+			let wp = WitnessProgram::from_address(network.hrp(), addr)
+				.map_err(|_| Error::<T>::InvalidBTCAddress)?;
+			ensure!(wp.version == 1 && wp.program.len() == 32, Error::<T>::InvalidBTCAddress);
+
+			let mut output_key = [0u8; 32];
+			output_key.copy_from_slice(&wp.program);
+			btc::bip322::verify_simple_p2tr(&output_key, msg, sig)
+				.map_err(|_| Error::<T>::SchnorrVerifyFailure)?;
+
+			// Only the lower case encoding is stored, so that an address can't be linked twice
+			wp.to_address(network.hrp().to_vec())
+				.map_err(|_| Error::<T>::InvalidBTCAddress.into())
+		}
+
+		/// Ensure that a link request has not expired and does not expire too far in the future
+		fn ensure_valid_expiring_block_number(
+			expiring_block_number: T::BlockNumber,
+		) -> DispatchResult {
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			ensure!(expiring_block_number > current_block_number, Error::<T>::LinkRequestExpired);
+			ensure!(
+				(expiring_block_number - current_block_number) < T::ExpiringBlockNumberMax::get(),
+				Error::<T>::InvalidExpiringBlockNumber
+			);
+			Ok(())
+		}
+
+		/// Whether `signer` signed `msg`, either raw or wrapped in `<Bytes>` and `</Bytes>`
+		fn verify_polkadot_signature(
+			sig: &T::PolkadotSignature,
+			msg: &[u8],
+			signer: &T::AccountId,
+		) -> bool {
+			let mut wrapped = b"<Bytes>".to_vec();
+			wrapped.extend_from_slice(msg);
+			wrapped.extend_from_slice(b"</Bytes>");
+			sig.verify(msg, signer) || sig.verify(&wrapped[..], signer)
+		}
+
+		/// Check the signature of a `refresh_link` request for `addr`, in the format of the link
+		/// call of its network
+		fn verify_refresh_signature(
+			account: &T::AccountId,
+			addr: &LinkedAddress<T::AccountId>,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			sig: &[u8],
+		) -> DispatchResult {
+			match addr {
+				LinkedAddress::Ethereum(a) => {
+					Self::ensure_evm_signer(
+						util_eth::ETH_MESSAGE_PREFIX,
+						account,
+						expiring_block_number,
+						nonce,
+						a,
+						sig,
+					)?;
+				},
+				LinkedAddress::Tron(a) => {
+					Self::ensure_evm_signer(
+						util_eth::TRON_MESSAGE_PREFIX,
+						account,
+						expiring_block_number,
+						nonce,
+						a,
+						sig,
+					)?;
+				},
+				LinkedAddress::Bitcoin(a) => {
+					let bytes = Self::generate_text_message(account, expiring_block_number, nonce);
+					let (addr, _) = Self::recover_btc_address(a, &bytes, sig)?;
+					ensure!(addr == *a, Error::<T>::UnexpectedAddress);
+				},
+				LinkedAddress::Solana(a) => {
+					let sig: [u8; 64] =
+						sig.try_into().map_err(|_| Error::<T>::InvalidSignatureLength)?;
+					let bytes = Self::generate_text_message(account, expiring_block_number, nonce);
+					ensure!(
+						ed25519_verify(
+							&ed25519::Signature::from_raw(sig),
+							&bytes,
+							&ed25519::Public::from_raw(*a)
+						),
+						Error::<T>::InvalidSolanaSignature
+					);
+				},
+				LinkedAddress::Cosmos(a) => {
+					let sig: [u8; 64] =
+						sig.try_into().map_err(|_| Error::<T>::InvalidSignatureLength)?;
+					let bytes = Self::generate_text_message(account, expiring_block_number, nonce);
+					let addr = Self::recover_adr036_address(a, &bytes, sig)?;
+					ensure!(addr == *a, Error::<T>::UnexpectedAddress);
+				},
+				LinkedAddress::Polkadot(a) => {
+					let sig = T::PolkadotSignature::decode(&mut &sig[..])
+						.map_err(|_| Error::<T>::InvalidSignatureLength)?;
+					let bytes = Self::generate_raw_message(account, expiring_block_number, nonce);
+					ensure!(
+						Self::verify_polkadot_signature(&sig, &bytes, a),
+						Error::<T>::InvalidPolkadotSignature
+					);
+				},
+			}
+			Ok(())
+		}
+
+		/// Ensure that `addr` signed the `generate_raw_message` message of `account` under the
+		/// signed message `prefix` of its Ethereum or Tron wallet
+		fn ensure_evm_signer(
+			prefix: &[u8],
+			account: &T::AccountId,
+			expiring_block_number: T::BlockNumber,
+			nonce: u32,
+			addr: &EthAddress,
+			sig: &[u8],
+		) -> DispatchResult {
+			let sig: Signature = sig.try_into().map_err(|_| Error::<T>::InvalidSignatureLength)?;
+			let bytes = Self::generate_raw_message(account, expiring_block_number, nonce);
+
+			let hash = util_eth::signed_data_hash(prefix, bytes);
+			let signer =
+				util_eth::addr_from_sig(hash, sig).map_err(|_| Error::<T>::EcdsaRecoverFailure)?;
+			ensure!(signer == *addr, Error::<T>::UnexpectedAddress);
+			Ok(())
+		}
+
+		/// Link the Litentry address `target` to `account`, shared by `accept_polkadot` and
+		/// `link_polkadot_signed`
+		fn do_link_polkadot(
+			account: &T::AccountId,
+			target: &T::AccountId,
+			mode: LinkMode,
+			proof: LinkProof,
+		) -> DispatchResult {
+			// NOTE: allow linking `MaxPolkadotLinks` polkadot addresses.
+			let replaced = PolkadotLink::<T>::try_mutate(account, |links| {
+				Self::insert_link(
+					links,
+					account,
+					mode,
+					target.clone(),
+					proof,
+					LinkedAddress::Polkadot,
+				)
+			})?;
+			if let Some(replaced) = replaced {
+				PolkadotLinkAccounts::<T>::remove(&replaced, account);
+				T::OnLinkChanged::on_unlinked(account, &LinkedAddress::Polkadot(replaced));
+			}
+			PolkadotLinkAccounts::<T>::insert(target, account, ());
+			T::OnLinkChanged::on_linked(account, &LinkedAddress::Polkadot(target.clone()));
+			Ok(())
+		}
+
+		/// Remove a pending `link_polkadot` request together with its expiry entry and return its
+		/// deposit
+		fn remove_polkadot_pending(
+			account: &T::AccountId,
+			target: &T::AccountId,
+			expiring_block_number: T::BlockNumber,
+		) {
+			<PolkadotPending<T>>::remove(account, target);
+			PolkadotPendingExpiry::<T>::remove(expiring_block_number, (account, target));
+			Self::release_deposit(account, T::PendingDeposit::get());
+		}
+
+		/// Remove a pending `link_eth_contract` request together with its expiry entry and return
+		/// its deposit
+		fn remove_eth_contract_pending(
+			account: &T::AccountId,
+			contract: &EthAddress,
+			expiring_block_number: T::BlockNumber,
+		) {
+			EthContractPending::<T>::remove(account, contract);
+			EthContractPendingExpiry::<T>::remove(expiring_block_number, (account, contract));
+			Self::release_deposit(account, T::PendingDeposit::get());
+		}
+
+		/// Record the attestation of `attester` on whether `contract` accepts the EIP-1271
+		/// signature of the pending `link_eth_contract` request of `account`. Called by the
+		/// offchain worker pallet with the result of the `isValidSignature` call.
+		///
+		/// The contract is linked once `ContractLinkQuorum` attesters approved the request, and the
+		/// request is dropped once as many rejected it or when it expired. Returns the number of
+		/// accounts the contract was transferred from when it was linked.
+		pub fn attest_eth_contract_link(
+			attester: T::AccountId,
+			account: T::AccountId,
+			contract: EthAddress,
+			valid: bool,
+		) -> Result<u32, DispatchError> {
+			ensure!(
+				T::ContractLinkAttesters::contains(&attester),
+				Error::<T>::NotContractLinkAttester
+			);
+			let mut request = Self::eth_contract_pending(&account, &contract)
+				.ok_or(Error::<T>::WrongPendingRequest)?;
+
+			if request.expiring_block_number <= <frame_system::Pallet<T>>::block_number() {
+				Self::remove_eth_contract_pending(
+					&account,
+					&contract,
+					request.expiring_block_number,
+				);
+				Self::deposit_event(Event::EthContractLinkExpired(account, contract.to_vec()));
+				return Ok(0)
+			}
+
+			ensure!(
+				!request.approvals.contains(&attester) && !request.rejections.contains(&attester),
+				Error::<T>::AlreadyAttested
+			);
+			let quorum = T::ContractLinkQuorum::get() as usize;
+			if valid {
+				// the approvals can only be full if the quorum was lowered since the request
+				let full = request.approvals.try_push(attester).is_err();
+				if full || request.approvals.len() >= quorum {
+					// the request is kept when the link fails, so it can be attested again once a
+					// slot is free
+					let displaced = Self::insert_eth_link(
+						&account,
+						request.mode,
+						contract,
+						LinkProof::Eip1271,
+					)?;
+					Self::remove_eth_contract_pending(
+						&account,
+						&contract,
+						request.expiring_block_number,
+					);
+					return Ok(displaced)
+				}
+			} else {
+				let full = request.rejections.try_push(attester).is_err();
+				if full || request.rejections.len() >= quorum {
+					Self::remove_eth_contract_pending(
+						&account,
+						&contract,
+						request.expiring_block_number,
+					);
+					Self::deposit_event(Event::EthContractLinkRejected(account, contract.to_vec()));
+					return Ok(0)
+				}
+			}
+			EthContractPending::<T>::insert(&account, &contract, request);
+
+			Ok(0)
+		}
+
+		/// All the pending `link_eth_contract` requests, for the offchain workers to attest
+		pub fn eth_contract_pending_requests(
+		) -> Vec<(T::AccountId, EthAddress, EthContractLinkRequestOf<T>)> {
+			EthContractPending::<T>::iter().collect()
+		}
+
+		/// Reserve `amount` from `account` and record it in `Deposits`
+		fn hold_deposit(account: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(())
+			}
+			T::Currency::reserve(account, amount)?;
+			Deposits::<T>::mutate(account, |held| {
+				*held = Some(held.unwrap_or_else(Zero::zero).saturating_add(amount))
+			});
+			Ok(())
+		}
+
+		/// Unreserve `amount` from `account`, capped by what `Deposits` recorded, so that links
+		/// made before deposits were introduced don't unreserve the funds of other pallets
+		fn release_deposit(account: &T::AccountId, amount: BalanceOf<T>) {
+			let released = Deposits::<T>::mutate_exists(account, |held| {
+				let total = held.unwrap_or_else(Zero::zero);
+				let released = amount.min(total);
+				let left = total - released;
+				*held = if left.is_zero() { None } else { Some(left) };
+				released
+			});
+			T::Currency::unreserve(account, released);
+		}
+
+		/// Remove the expired `link_polkadot` requests, going through at most `PendingSweepLimit`
+		/// blocks and requests.
+		///
+		/// The sweep resumes where it stopped in the previous block, so a large number of requests
+		/// expiring at the same block is spread over several blocks.
+		pub(crate) fn sweep_expired_polkadot_pending(now: T::BlockNumber) -> Weight {
+			let mut budget = T::PendingSweepLimit::get() as usize;
+			let mut block = PolkadotPendingSweep::<T>::get();
+			let mut reads = 1u64;
+			let mut writes = 0u64;
+
+			while block <= now && budget > 0 {
+				// one more than the budget, to know whether this block is done
+				let expired: Vec<(T::AccountId, T::AccountId)> =
+					PolkadotPendingExpiry::<T>::iter_prefix(block)
+						.take(budget + 1)
+						.map(|(key, _)| key)
+						.collect();
+				reads += expired.len() as u64 + 1;
+
+				let done = expired.len() <= budget;
+				for (account, target) in expired.into_iter().take(budget) {
+					budget -= 1;
+					Self::remove_polkadot_pending(&account, &target, block);
+					// the request, its expiry entry, the deposit record and the reserved balance
+					reads += 2;
+					writes += 4;
+					Self::deposit_event(Event::PolkadotLinkExpired(account, target));
+				}
+
+				if !done {
+					break
+				}
+				// blocks without expired requests count too, so that catching up is spread as well
+				budget = budget.saturating_sub(1);
+				block = block.saturating_add(One::one());
+			}
+
+			PolkadotPendingSweep::<T>::put(block);
+			T::DbWeight::get().reads_writes(reads, writes + 1)
+		}
+
+		/// Remove the expired `link_eth_contract` requests, going through at most
+		/// `PendingSweepLimit` blocks and requests, like `sweep_expired_polkadot_pending`.
+		pub(crate) fn sweep_expired_eth_contract_pending(now: T::BlockNumber) -> Weight {
+			let mut budget = T::PendingSweepLimit::get() as usize;
+			let mut block = EthContractPendingSweep::<T>::get();
+			let mut reads = 1u64;
+			let mut writes = 0u64;
+
+			while block <= now && budget > 0 {
+				// one more than the budget, to know whether this block is done
+				let expired: Vec<(T::AccountId, EthAddress)> =
+					EthContractPendingExpiry::<T>::iter_prefix(block)
+						.take(budget + 1)
+						.map(|(key, _)| key)
+						.collect();
+				reads += expired.len() as u64 + 1;
+
+				let done = expired.len() <= budget;
+				for (account, contract) in expired.into_iter().take(budget) {
+					budget -= 1;
+					Self::remove_eth_contract_pending(&account, &contract, block);
+					// the request, its expiry entry, the deposit record and the reserved balance
+					reads += 2;
+					writes += 4;
+					Self::deposit_event(Event::EthContractLinkExpired(account, contract.to_vec()));
+				}
+
+				if !done {
+					break
+				}
+				budget = budget.saturating_sub(1);
+				block = block.saturating_add(One::one());
+			}
+
+			EthContractPendingSweep::<T>::put(block);
+			T::DbWeight::get().reads_writes(reads, writes + 1)
+		}
+
+		/// Index the link of `addr` to `account` under the block it goes stale at, if links ever
+		/// do, so that `sweep_stale_links` announces it
+		fn schedule_stale_link(
+			account: &T::AccountId,
+			addr: LinkedAddress<T::AccountId>,
+			verified_at: T::BlockNumber,
+		) {
+			if let Some(period) = T::LinkValidityPeriod::get() {
+				LinkExpiry::<T>::insert(verified_at.saturating_add(period), (account, addr), ());
+			}
+		}
+
+		/// Emit `LinkStale` for the links that went stale, going through at most `StaleSweepLimit`
+		/// blocks and `LinkExpiry` entries.
+		///
+		/// An entry whose link was refreshed meanwhile is moved to the block the link now goes
+		/// stale at, the entries of unlinked addresses are dropped.
+		pub(crate) fn sweep_stale_links(now: T::BlockNumber) -> Weight {
+			let mut budget = T::StaleSweepLimit::get() as usize;
+			let mut block = LinkExpirySweep::<T>::get();
+			let mut reads = 1u64;
+			let mut writes = 0u64;
+
+			while block <= now && budget > 0 {
+				// one more than the budget, to know whether this block is done
+				let due: Vec<(T::AccountId, LinkedAddress<T::AccountId>)> =
+					LinkExpiry::<T>::iter_prefix(block)
+						.take(budget + 1)
+						.map(|(key, _)| key)
+						.collect();
+				reads += due.len() as u64 + 1;
+
+				let done = due.len() <= budget;
+				for (account, addr) in due.into_iter().take(budget) {
+					budget -= 1;
+					LinkExpiry::<T>::remove(block, (&account, &addr));
+					// the linked address list and the removed entry
+					reads += 1;
+					writes += 1;
+
+					let stale_at = match (
+						Self::link_verified_at(&account, &addr),
+						T::LinkValidityPeriod::get(),
+					) {
+						(Some(verified_at), Some(period)) => verified_at.saturating_add(period),
+						// unlinked, or links no longer go stale
+						_ => continue,
+					};
+					if stale_at <= block {
+						Self::deposit_event(Event::LinkStale(account, addr));
+					} else {
+						LinkExpiry::<T>::insert(stale_at, (account, addr), ());
+						writes += 1;
+					}
+				}
+
+				if !done {
+					break
+				}
+				// blocks without stale links count too, so that catching up is spread as well
+				budget = budget.saturating_sub(1);
+				block = block.saturating_add(One::one());
+			}
+
+			LinkExpirySweep::<T>::put(block);
+			T::DbWeight::get().reads_writes(reads, writes + 1)
+		}
+
+		/// The block the ownership of `addr` was last proven at, the latest of its slots if it is
+		/// linked at several ones, or `None` if it isn't linked to `account`
+		fn link_verified_at(
+			account: &T::AccountId,
+			addr: &LinkedAddress<T::AccountId>,
+		) -> Option<T::BlockNumber> {
+			fn latest<A: PartialEq, B: Copy + Ord>(
+				links: &[LinkRecord<A, B>],
+				addr: &A,
+			) -> Option<B> {
+				links.iter().filter(|l| l.address == *addr).map(|l| l.verified_at).max()
+			}
+
+			match addr {
+				LinkedAddress::Ethereum(a) => latest(&Self::eth_links(account), a),
+				LinkedAddress::Bitcoin(a) => latest(&Self::btc_links(account), a),
+				LinkedAddress::Tron(a) => latest(&Self::tron_links(account), a),
+				LinkedAddress::Solana(a) => latest(&Self::solana_links(account), a),
+				LinkedAddress::Cosmos(a) => latest(&Self::cosmos_links(account), a),
+				LinkedAddress::Polkadot(a) => latest(&Self::polkadot_links(account), a),
+			}
+		}
+
+		/// Whether a link whose ownership was last proven at `verified_at` is stale
+		fn is_stale(verified_at: T::BlockNumber) -> bool {
+			T::LinkValidityPeriod::get().map_or(false, |period| {
+				verified_at.saturating_add(period) <= <frame_system::Pallet<T>>::block_number()
+			})
+		}
+
+		/// Whether the link of `addr` to `account` wasn't refreshed within `LinkValidityPeriod`,
+		/// `false` if it isn't linked
+		pub fn is_link_stale(account: &T::AccountId, addr: &LinkedAddress<T::AccountId>) -> bool {
+			Self::link_verified_at(account, addr).map_or(false, Self::is_stale)
+		}
+
+		/// Ensure that `nonce` is the next link nonce of `account`, so that a link signature
+		/// cannot be replayed once it has been used
+		fn ensure_valid_link_nonce(account: &T::AccountId, nonce: u32) -> DispatchResult {
+			ensure!(nonce == Self::link_nonce(account), Error::<T>::StaleLinkNonce);
+			Ok(())
+		}
+
+		fn increment_link_nonce(account: &T::AccountId) {
+			LinkNonce::<T>::mutate(account, |nonce| *nonce = nonce.saturating_add(1));
+		}
+
+		/// Recover the Ethereum address that signed `msg` with the `proof` scheme and link it to
+		/// `account`, returning the number of accounts it was transferred from.
+		fn do_link_eth(
+			account: T::AccountId,
+			mode: LinkMode,
+			addr_expected: EthAddress,
+			msg: [u8; 32],
+			sig: Signature,
+			proof: LinkProof,
+		) -> Result<u32, DispatchError> {
+			let addr =
+				util_eth::addr_from_sig(msg, sig).map_err(|_| Error::<T>::EcdsaRecoverFailure)?;
+			ensure!(addr == addr_expected, Error::<T>::UnexpectedAddress);
+			let displaced = Self::insert_eth_link(&account, mode, addr, proof)?;
+			Self::increment_link_nonce(&account);
+
+			Ok(displaced)
+		}
+
+		/// Link the Ethereum address `addr` to `account` once its ownership is proven, applying
+		/// `T::LinkExclusivity`. Returns the number of accounts it was transferred from.
+		fn insert_eth_link(
+			account: &T::AccountId,
+			mode: LinkMode,
+			addr: EthAddress,
+			proof: LinkProof,
+		) -> Result<u32, DispatchError> {
+			let displaced = Self::displaced_accounts(account, || {
+				EthereumLinkAccounts::<T>::iter_prefix(&addr)
+			})?;
+			let count = displaced.len() as u32;
+
+			// NOTE: allow linking `MaxEthLinks` eth addresses.
+			let replaced = EthereumLink::<T>::try_mutate(account, |links| {
+				Self::insert_link(links, account, mode, addr, proof, LinkedAddress::Ethereum)
+			})?;
+			if let Some(replaced) = replaced {
+				EthereumLinkAccounts::<T>::remove(&replaced, account);
+				T::OnLinkChanged::on_unlinked(account, &LinkedAddress::Ethereum(replaced));
+			}
+			EthereumLinkAccounts::<T>::insert(&addr, account, ());
+
+			for other in displaced {
+				EthereumLink::<T>::mutate_exists(&other, |links| {
+					Self::evict_link(links, &other, &addr)
+				});
+				EthereumLinkAccounts::<T>::remove(&addr, &other);
+				T::OnLinkChanged::on_unlinked(&other, &LinkedAddress::Ethereum(addr));
+				Self::deposit_event(Event::EthAddressTransferred(
+					other,
+					account.clone(),
+					addr.to_vec(),
+				));
+			}
+
+			T::OnLinkChanged::on_linked(account, &LinkedAddress::Ethereum(addr));
+			Self::deposit_event(Event::EthAddressLinked(account.clone(), addr.to_vec()));
+
+			Ok(count)
+		}
+
+		/// Recover the Cosmos SDK address of the key that produced an ADR-036 signature of `msg`
+		/// for `signer`.
+		///
+		/// The signature carries no recovery id, so both candidate keys are derived and the one
+		/// matching `signer` is returned, if any.
+		fn recover_adr036_address(
+			signer: &[u8],
+			msg: &[u8],
+			sig: [u8; 64],
+		) -> Result<Vec<u8>, DispatchError> {
+			let hash = sp_io::hashing::sha2_256(&cosmos::adr036_sign_doc(signer, msg));
+
+			let mut rsv: Signature = [0u8; 65];
+			rsv[..64].copy_from_slice(&sig);
+			let mut addr = Err(Error::<T>::EcdsaRecoverFailure.into());
+			for recovery_id in 0..2 {
+				rsv[64] = recovery_id;
+				if let Ok(pk) = secp256k1_ecdsa_recover_compressed(&rsv, &hash) {
+					let recovered = cosmos::address_from_pk(T::CosmosHrp::get(), &pk)
+						.map_err(|_| Error::<T>::InvalidCosmosAddress)?;
+					if recovered == signer {
+						return Ok(recovered)
+					}
+					addr = Ok(recovered);
+				}
+			}
+			addr
+		}
+
+		/// Link `addr` to a linked address list according to `mode`. Shared by all the link calls
+		/// so that no linked address is ever overwritten implicitly.
+		///
+		/// A new slot takes `LinkDeposit` from `owner`, the whole mutation is discarded by the
+		/// caller's `try_mutate` when it can't be reserved. The slot records the current block and
+		/// `proof`, and the link is scheduled to go stale with `key` identifying it. An address
+		/// takes a single slot, it can only be linked again at that slot to refresh it.
+		///
+		/// Returns the replaced address if it is no longer linked, so that its reverse index entry
+		/// can be removed.
+		fn insert_link<A: Clone + PartialEq, S: Get<u32>>(
+			links: &mut BoundedVec<LinkRecord<A, T::BlockNumber>, S>,
+			owner: &T::AccountId,
+			mode: LinkMode,
+			addr: A,
+			proof: LinkProof,
+			key: fn(A) -> LinkedAddress<T::AccountId>,
+		) -> Result<Option<A>, DispatchError> {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let key = key(addr.clone());
+			let record =
+				LinkRecord { address: addr, linked_at: now, verified_at: now, proof, label: None };
+			let replaced = match mode {
+				LinkMode::Append => {
+					ensure!(!Self::has_link(links, &record.address), Error::<T>::AlreadyLinked);
+					links.try_push(record).map_err(|_| Error::<T>::LinkSlotsFull)?;
+					Self::hold_deposit(owner, T::LinkDeposit::get())?;
+					None
+				},
+				LinkMode::Replace(index) => {
+					let index = index as usize;
+					ensure!(index < links.len(), Error::<T>::InvalidLinkIndex);
+					// the address may only be linked again at its own slot
+					ensure!(
+						links
+							.iter()
+							.enumerate()
+							.all(|(i, l)| i == index || l.address != record.address),
+						Error::<T>::AlreadyLinked
+					);
+					let replaced = links.remove(index).address;
+					// can't fail, a slot was just freed
+					let _ = links.try_insert(index, record);
+					Some(replaced).filter(|replaced| !Self::has_link(links, replaced))
+				},
+			};
+			Self::schedule_stale_link(owner, key, now);
+			Ok(replaced)
+		}
+
+		/// Remove `addr` from a linked address list, dropping the storage entry once the last
+		/// linked address is gone, and return the deposit of its slot to `owner`.
+		///
+		/// Returns whether `addr` is no longer linked, i.e. it wasn't linked at several slots.
+		fn remove_link<A: PartialEq, S>(
+			maybe_links: &mut Option<BoundedVec<LinkRecord<A, T::BlockNumber>, S>>,
+			owner: &T::AccountId,
+			addr: &A,
+		) -> Result<bool, DispatchError> {
+			let links = maybe_links.as_mut().ok_or(Error::<T>::LinkNotFound)?;
+			let index =
+				links.iter().position(|l| l.address == *addr).ok_or(Error::<T>::LinkNotFound)?;
+			links.remove(index);
+			Self::release_deposit(owner, T::LinkDeposit::get());
+			let unlinked = !Self::has_link(links, addr);
+			if links.is_empty() {
+				*maybe_links = None;
+			}
+			Ok(unlinked)
+		}
+
+		/// Remove every occurrence of `addr` from the linked address list of the displaced account
+		/// `owner`, returning the deposits of the freed slots
+		fn evict_link<A: PartialEq, S>(
+			maybe_links: &mut Option<BoundedVec<LinkRecord<A, T::BlockNumber>, S>>,
+			owner: &T::AccountId,
+			addr: &A,
+		) {
+			if let Some(links) = maybe_links {
+				while let Some(index) = links.iter().position(|l| l.address == *addr) {
+					links.remove(index);
+					Self::release_deposit(owner, T::LinkDeposit::get());
+				}
+				if links.is_empty() {
+					*maybe_links = None;
+				}
+			}
+		}
+
+		/// Apply `update` to every slot `addr` is linked at, fails when it isn't linked
+		fn update_links<A: PartialEq, S: Get<u32>>(
+			maybe_links: &mut Option<BoundedVec<LinkRecord<A, T::BlockNumber>, S>>,
+			addr: &A,
+			update: impl Fn(&mut LinkRecord<A, T::BlockNumber>),
+		) -> DispatchResult {
+			let links = maybe_links.as_mut().ok_or(Error::<T>::LinkNotFound)?;
+			ensure!(Self::has_link(links, addr), Error::<T>::LinkNotFound);
+			for index in 0..links.len() {
+				if links[index].address == *addr {
+					let mut link = links.remove(index);
+					update(&mut link);
+					// can't fail, a slot was just freed
+					let _ = links.try_insert(index, link);
+				}
+			}
+			Ok(())
+		}
+
+		/// Whether `addr` is linked at any slot of `links`
+		fn has_link<A: PartialEq>(links: &[LinkRecord<A, T::BlockNumber>], addr: &A) -> bool {
+			links.iter().any(|l| l.address == *addr)
+		}
+
+		/// Apply `T::LinkExclusivity` to the Litentry accounts an address is linked to before
+		/// linking it to `account`.
+		///
+		/// Returns the other accounts that the address has to be unlinked from, fails when there
+		/// are more than `T::MaxDisplacedAccounts` of them. `linked` iterates the reverse index of the address,
+		/// it is only read when the address isn't shared and only as far as needed.
+		fn displaced_accounts<I: Iterator<Item = (T::AccountId, ())>>(
+			account: &T::AccountId,
+			linked: impl FnOnce() -> I,
+		) -> Result<Vec<T::AccountId>, DispatchError> {
+			let policy = T::LinkExclusivity::get();
+			if policy == LinkExclusivity::Shared {
+				return Ok(Vec::new())
+			}
+
+			let mut others = linked().map(|(other, _)| other).filter(|other| other != account);
+			if policy == LinkExclusivity::Reject {
+				ensure!(others.next().is_none(), Error::<T>::AddressAlreadyLinked);
+				return Ok(Vec::new())
+			}
+			// one past the bound tells whether the address is linked to too many accounts
+			let max = T::MaxDisplacedAccounts::get() as usize;
+			let displaced: Vec<_> = others.take(max.saturating_add(1)).collect();
+			ensure!(displaced.len() <= max, Error::<T>::TooManyDisplacedAccounts);
+			Ok(displaced)
+		}
+
+		/// The Ethereum addresses linked to `account`, in slot order
+		pub fn eth_addresses(account: &T::AccountId) -> Vec<EthAddress> {
+			Self::eth_links(account).into_iter().map(|link| link.address).collect()
+		}
+
+		/// The Ethereum addresses linked to `account` that aren't stale, in slot order
+		pub fn fresh_eth_addresses(account: &T::AccountId) -> Vec<EthAddress> {
+			Self::eth_links(account)
+				.into_iter()
+				.filter(|link| !Self::is_stale(link.verified_at))
+				.map(|link| link.address)
+				.collect()
+		}
+
+		/// The BTC addresses linked to `account`, in slot order
+		pub fn btc_addresses(account: &T::AccountId) -> Vec<Vec<u8>> {
+			Self::btc_links(account).into_iter().map(|link| link.address).collect()
+		}
+
+		/// The Tron addresses linked to `account`, in slot order
+		pub fn tron_addresses(account: &T::AccountId) -> Vec<EthAddress> {
+			Self::tron_links(account).into_iter().map(|link| link.address).collect()
+		}
+
+		/// The Solana addresses linked to `account`, in slot order
+		pub fn solana_addresses(account: &T::AccountId) -> Vec<SolanaAddress> {
+			Self::solana_links(account).into_iter().map(|link| link.address).collect()
+		}
+
+		/// The Cosmos SDK addresses linked to `account`, in slot order
+		pub fn cosmos_addresses(account: &T::AccountId) -> Vec<Vec<u8>> {
+			Self::cosmos_links(account).into_iter().map(|link| link.address).collect()
+		}
+
+		/// The Litentry addresses linked to `account`, in slot order
+		pub fn polkadot_addresses(account: &T::AccountId) -> Vec<T::AccountId> {
+			Self::polkadot_links(account).into_iter().map(|link| link.address).collect()
+		}
+
+		/// The Litentry accounts the Ethereum address `addr` is linked to
+		pub fn eth_linked_accounts(addr: &EthAddress) -> Vec<T::AccountId> {
+			EthereumLinkAccounts::<T>::iter_prefix(addr)
+				.map(|(account, _)| account)
+				.collect()
+		}
+
+		/// The Litentry accounts the BTC address `addr` is linked to
+		pub fn btc_linked_accounts(addr: &[u8]) -> Vec<T::AccountId> {
+			BitcoinLinkAccounts::<T>::iter_prefix(addr)
+				.map(|(account, _)| account)
+				.collect()
+		}
+
+		/// The Litentry accounts the Tron address `addr` is linked to
+		pub fn tron_linked_accounts(addr: &EthAddress) -> Vec<T::AccountId> {
+			TronLinkAccounts::<T>::iter_prefix(addr).map(|(account, _)| account).collect()
+		}
+
+		/// The Litentry accounts the Solana address `addr` is linked to
+		pub fn solana_linked_accounts(addr: &SolanaAddress) -> Vec<T::AccountId> {
+			SolanaLinkAccounts::<T>::iter_prefix(addr).map(|(account, _)| account).collect()
+		}
+
+		/// The Litentry accounts the Cosmos SDK address `addr` is linked to
+		pub fn cosmos_linked_accounts(addr: &[u8]) -> Vec<T::AccountId> {
+			CosmosLinkAccounts::<T>::iter_prefix(addr).map(|(account, _)| account).collect()
+		}
+
+		/// The Litentry accounts the Litentry address `addr` is linked to
+		pub fn polkadot_linked_accounts(addr: &T::AccountId) -> Vec<T::AccountId> {
+			PolkadotLinkAccounts::<T>::iter_prefix(addr)
+				.map(|(account, _)| account)
+				.collect()
+		}
+
+		/// Whether the Ethereum address `addr` is linked to `account`
+		pub fn is_eth_linked(addr: &EthAddress, account: &T::AccountId) -> bool {
+			EthereumLinkAccounts::<T>::contains_key(addr, account)
+		}
+
+		/// Whether the BTC address `addr` is linked to `account`
+		pub fn is_btc_linked(addr: &[u8], account: &T::AccountId) -> bool {
+			BitcoinLinkAccounts::<T>::contains_key(addr, account)
+		}
+
+		/// Whether the Tron address `addr` is linked to `account`
+		pub fn is_tron_linked(addr: &EthAddress, account: &T::AccountId) -> bool {
+			TronLinkAccounts::<T>::contains_key(addr, account)
+		}
+
+		/// Whether the Solana address `addr` is linked to `account`
+		pub fn is_solana_linked(addr: &SolanaAddress, account: &T::AccountId) -> bool {
+			SolanaLinkAccounts::<T>::contains_key(addr, account)
+		}
+
+		/// Whether the Cosmos SDK address `addr` is linked to `account`
+		pub fn is_cosmos_linked(addr: &[u8], account: &T::AccountId) -> bool {
+			CosmosLinkAccounts::<T>::contains_key(addr, account)
+		}
+
+		/// Whether the Litentry address `addr` is linked to `account`
+		pub fn is_polkadot_linked(addr: &T::AccountId, account: &T::AccountId) -> bool {
+			PolkadotLinkAccounts::<T>::contains_key(addr, account)
+		}
+
+		/// Everything linked to `account` with the addresses in their display encodings, the
+		/// `linked_identity` call of the `AccountLinkerApi` runtime API
+		pub fn linked_identity(
+			account: &T::AccountId,
+		) -> LinkedIdentity<T::AccountId, T::BlockNumber> {
+			fn display<A, B>(
+				links: &[LinkRecord<A, B>],
+				encode: impl Fn(&A) -> Vec<u8>,
+				is_stale: fn(B) -> bool,
+			) -> Vec<IdentityLink<B>>
+			where
+				B: Copy,
+			{
+				links
+					.iter()
+					.map(|link| IdentityLink {
+						address: encode(&link.address),
+						linked_at: link.linked_at,
+						verified_at: link.verified_at,
+						stale: is_stale(link.verified_at),
+						proof: link.proof,
+						label: link.label.clone(),
+					})
+					.collect()
+			}
+
+			LinkedIdentity {
+				ethereum: display(
+					&Self::eth_links(account),
+					|addr| [&b"0x"[..], &btc::message::to_hex(addr)].concat(),
+					Self::is_stale,
+				),
+				bitcoin: display(&Self::btc_links(account), Clone::clone, Self::is_stale),
+				tron: display(&Self::tron_links(account), util_eth::tron_address, Self::is_stale),
+				solana: display(
+					&Self::solana_links(account),
+					|addr| addr.to_base58(),
+					Self::is_stale,
+				),
+				cosmos: display(&Self::cosmos_links(account), Clone::clone, Self::is_stale),
+				polkadot: Self::polkadot_links(account)
+					.into_iter()
+					.map(|link| PolkadotIdentityLink {
+						account: link.address,
+						linked_at: link.linked_at,
+						verified_at: link.verified_at,
+						stale: Self::is_stale(link.verified_at),
+						proof: link.proof,
+						label: link.label,
+					})
+					.collect(),
+				pending_polkadot: PolkadotPending::<T>::iter_prefix(account)
+					.map(|(target, request)| PendingPolkadotLink { target, request })
+					.collect(),
+			}
+		}
+	}
+}
+
+impl<T: Config> LinkedAccountsProvider<T::AccountId> for Pallet<T> {
+	fn eth_addresses(account: &T::AccountId) -> Vec<EthAddress> {
+		Self::eth_addresses(account)
+	}
+
+	fn fresh_eth_addresses(account: &T::AccountId) -> Vec<EthAddress> {
+		Self::fresh_eth_addresses(account)
+	}
+
+	fn btc_addresses(account: &T::AccountId) -> Vec<Vec<u8>> {
+		Self::btc_addresses(account)
+	}
+
+	fn tron_addresses(account: &T::AccountId) -> Vec<EthAddress> {
+		Self::tron_addresses(account)
+	}
+
+	fn solana_addresses(account: &T::AccountId) -> Vec<SolanaAddress> {
+		Self::solana_addresses(account)
+	}
+
+	fn cosmos_addresses(account: &T::AccountId) -> Vec<Vec<u8>> {
+		Self::cosmos_addresses(account)
+	}
+
+	fn polkadot_addresses(account: &T::AccountId) -> Vec<T::AccountId> {
+		Self::polkadot_addresses(account)
+	}
+
+	fn is_link_stale(account: &T::AccountId, addr: &LinkedAddress<T::AccountId>) -> bool {
+		Self::is_link_stale(account, addr)
+	}
+}
+
+impl<T: Config> ContractLinkAttestation<T::AccountId, T::BlockNumber> for Pallet<T> {
+	type MaxSignatureLen = T::MaxContractSignatureLen;
+	type Quorum = T::ContractLinkQuorum;
+	type MaxDisplacedAccounts = T::MaxDisplacedAccounts;
+
+	fn pending_requests() -> Vec<(T::AccountId, EthAddress, EthContractLinkRequestOf<T>)> {
+		Self::eth_contract_pending_requests()
+	}
+
+	fn attest(
+		attester: T::AccountId,
+		account: T::AccountId,
+		contract: EthAddress,
+		valid: bool,
+	) -> Result<u32, sp_runtime::DispatchError> {
+		Self::attest_eth_contract_link(attester, account, contract, valid)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_attestable_request(
+		attester: &T::AccountId,
+		account: &T::AccountId,
+		contract: EthAddress,
+		displaced: u32,
+	) {
+		use frame_support::traits::SortedMembers;
+		use sp_runtime::traits::Saturating;
+		use sp_std::convert::TryFrom;
+
+		T::ContractLinkAttesters::add(attester);
+		let mode = benchmarking::fill_eth_slots::<T>(account, contract, displaced);
+		let approvals = (1..T::ContractLinkQuorum::get())
+			.map(|i| frame_benchmarking::account("approval", i, 0))
+			.collect::<Vec<T::AccountId>>();
+		let signature = vec![0u8; T::MaxContractSignatureLen::get() as usize];
+		let expiring_block_number = <frame_system::Pallet<T>>::block_number()
+			.saturating_add(T::ExpiringBlockNumberMax::get());
+		EthContractPendingExpiry::<T>::insert(expiring_block_number, (account, contract), ());
+		EthContractPending::<T>::insert(
+			account,
+			&contract,
+			EthContractLinkRequest {
+				mode,
+				expiring_block_number,
+				hash: [0u8; 32],
+				signature: BoundedVec::try_from(signature).unwrap_or_default(),
+				approvals: BoundedVec::try_from(approvals).unwrap_or_default(),
+				rejections: Default::default(),
+			},
+		);
 	}
 }
@@ -0,0 +1,366 @@
+//! Storage migrations of the account-linker pallet
+
+use super::*;
+use frame_support::{
+	storage::migration::{put_storage_value, storage_key_iter},
+	traits::{Get, PalletInfoAccess},
+	weights::Weight,
+	Blake2_128Concat, BoundedVec, StorageHasher,
+};
+use sp_std::{convert::TryFrom, prelude::*};
+
+/// The linked address lists of the storage `item` as they were stored before v5, without
+/// `LinkRecord`. `Vec` and `BoundedVec` are encoded the same way.
+fn bare_links<T: Config, A: Decode>(item: &[u8]) -> Vec<(T::AccountId, Vec<A>)> {
+	storage_key_iter::<T::AccountId, Vec<A>, Blake2_128Concat>(<Pallet<T>>::name().as_bytes(), item)
+		.collect()
+}
+
+fn truncate<A, S: Get<u32>>(mut addrs: Vec<A>) -> BoundedVec<A, S> {
+	addrs.truncate(S::get() as usize);
+	// can't fail, the list was just truncated to the bound
+	BoundedVec::try_from(addrs).unwrap_or_default()
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Migrate the linked address lists from `Vec` to `BoundedVec`.
+	///
+	/// Both are encoded the same way, so the lists are only truncated to the limits configured
+	/// by `MaxEthLinks`, `MaxBtcLinks` and `MaxPolkadotLinks`.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V1_0_0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let translated = truncate_links::<T, EthAddress>(b"EthereumLink", T::MaxEthLinks::get()) +
+			truncate_links::<T, Vec<u8>>(b"BitcoinLink", T::MaxBtcLinks::get()) +
+			truncate_links::<T, T::AccountId>(b"PolkadotLink", T::MaxPolkadotLinks::get());
+
+		StorageVersion::<T>::put(Releases::V2_0_0);
+
+		log::info!(target: "account-linker", "migrated {} linked address lists to v2", translated);
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+
+	// The lists are still stored without `LinkRecord` at v2
+	fn truncate_links<T: Config, A: Decode + Encode>(item: &[u8], max: u32) -> u64 {
+		let lists = bare_links::<T, A>(item);
+		let translated = lists.len() as u64;
+		for (account, mut addrs) in lists {
+			addrs.truncate(max as usize);
+			put_storage_value(
+				<Pallet<T>>::name().as_bytes(),
+				item,
+				&Blake2_128Concat::hash(&account.encode()),
+				addrs,
+			);
+		}
+		translated
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Build the reverse indexes `EthereumLinkAccounts`, `BitcoinLinkAccounts` and
+	/// `PolkadotLinkAccounts` from the existing linked address lists.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V2_0_0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut reads = 0u64;
+		let mut writes = 0u64;
+		for (account, addrs) in bare_links::<T, EthAddress>(b"EthereumLink") {
+			reads += 1;
+			for addr in addrs.iter() {
+				EthereumLinkAccounts::<T>::insert(addr, &account, ());
+				writes += 1;
+			}
+		}
+		for (account, addrs) in bare_links::<T, Vec<u8>>(b"BitcoinLink") {
+			reads += 1;
+			for addr in addrs.iter() {
+				BitcoinLinkAccounts::<T>::insert(addr, &account, ());
+				writes += 1;
+			}
+		}
+		for (account, addrs) in bare_links::<T, T::AccountId>(b"PolkadotLink") {
+			reads += 1;
+			for addr in addrs.iter() {
+				PolkadotLinkAccounts::<T>::insert(addr, &account, ());
+				writes += 1;
+			}
+		}
+
+		StorageVersion::<T>::put(Releases::V3_0_0);
+
+		log::info!(target: "account-linker", "indexed {} linked addresses in v3", writes);
+
+		T::DbWeight::get().reads_writes(reads + 1, writes + 1)
+	}
+}
+
+pub mod v4 {
+	use super::*;
+	use frame_support::storage::migration::get_storage_value;
+	use sp_runtime::traits::Saturating;
+
+	/// Move the single pending `link_polkadot` request of each account, stored as
+	/// `(target, index)`, to the double map `PolkadotPending`, giving it the longest allowed
+	/// expiry. The slot index becomes the `LinkMode` that accepting the request applied.
+	///
+	/// The requests had no expiry before, the sweep of expired requests starts at the current
+	/// block.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V3_0_0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let expiring_block_number = now.saturating_add(T::ExpiringBlockNumberMax::get());
+
+		// The requests still hold the slot index of the target they were made with
+		let requests: Vec<(T::AccountId, (T::AccountId, u32))> =
+			storage_key_iter::<T::AccountId, (T::AccountId, u32), Blake2_128Concat>(
+				<Pallet<T>>::name().as_bytes(),
+				b"PolkadotPending",
+			)
+			.drain()
+			.collect();
+
+		let moved = requests.len() as u64;
+		for (account, (target, index)) in requests {
+			let linked = get_storage_value::<Vec<T::AccountId>>(
+				<Pallet<T>>::name().as_bytes(),
+				b"PolkadotLink",
+				&Blake2_128Concat::hash(&account.encode()),
+			)
+			.map_or(0, |links| links.len() as u32);
+			let mode = link_mode(index, linked, T::MaxPolkadotLinks::get());
+			PolkadotPendingExpiry::<T>::insert(expiring_block_number, (&account, &target), ());
+			PolkadotPending::<T>::insert(
+				&account,
+				&target,
+				PolkadotLinkRequest { mode, expiring_block_number },
+			);
+		}
+
+		PolkadotPendingSweep::<T>::put(now);
+		StorageVersion::<T>::put(Releases::V4_0_0);
+
+		log::info!(target: "account-linker", "migrated {} pending polkadot links to v4", moved);
+
+		T::DbWeight::get().reads_writes(moved * 2 + 1, moved * 3 + 2)
+	}
+
+	/// The `LinkMode` of a request made with the slot `index` of a list of `linked` addresses.
+	///
+	/// An index past the end of the list appended the target, or replaced the last slot when
+	/// the list was full.
+	pub fn link_mode(index: u32, linked: u32, max: u32) -> LinkMode {
+		if index < linked {
+			LinkMode::Replace(index)
+		} else if linked < max {
+			LinkMode::Append
+		} else {
+			LinkMode::Replace(linked.saturating_sub(1))
+		}
+	}
+}
+
+pub mod v5 {
+	use super::*;
+
+	/// A slot of a linked address list as it was stored at v5, before `verified_at`
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct LinkRecord<Address, BlockNumber> {
+		pub address: Address,
+		pub linked_at: BlockNumber,
+		pub proof: LinkProof,
+		pub label: Option<Vec<u8>>,
+	}
+
+	/// Turn every slot of the linked address lists into a `LinkRecord`.
+	///
+	/// The linking block of the addresses linked before it was recorded is the current block,
+	/// the proof of all the migrated links is `LinkProof::Unknown`.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V4_0_0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let migrated = [
+			record_links::<T, EthAddress>(b"EthereumLink", T::MaxEthLinks::get(), now),
+			record_links::<T, Vec<u8>>(b"BitcoinLink", T::MaxBtcLinks::get(), now),
+			record_links::<T, EthAddress>(b"TronLink", T::MaxTronLinks::get(), now),
+			record_links::<T, SolanaAddress>(b"SolanaLink", T::MaxSolanaLinks::get(), now),
+			record_links::<T, Vec<u8>>(b"CosmosLink", T::MaxCosmosLinks::get(), now),
+			record_links::<T, T::AccountId>(b"PolkadotLink", T::MaxPolkadotLinks::get(), now),
+		];
+		let lists: u64 = migrated.iter().map(|(lists, _)| lists).sum();
+		let slots: u64 = migrated.iter().map(|(_, slots)| slots).sum();
+
+		StorageVersion::<T>::put(Releases::V5_0_0);
+
+		log::info!(target: "account-linker", "migrated {} linked addresses to v5", slots);
+
+		T::DbWeight::get().reads_writes(lists + 1, lists + 1)
+	}
+
+	// Rewrite the lists of the storage `item` with the v5 records of their addresses. Returns the
+	// number of lists and slots.
+	fn record_links<T: Config, A: Clone + Decode + Encode>(
+		item: &[u8],
+		max: u32,
+		now: T::BlockNumber,
+	) -> (u64, u64) {
+		let lists = bare_links::<T, A>(item);
+		let mut slots = 0u64;
+		for (account, addrs) in lists.iter() {
+			slots += addrs.len() as u64;
+			let records: Vec<LinkRecord<A, T::BlockNumber>> = addrs
+				.iter()
+				.take(max as usize)
+				.map(|address| LinkRecord {
+					address: address.clone(),
+					linked_at: now,
+					proof: LinkProof::Unknown,
+					label: None,
+				})
+				.collect();
+			put_storage_value(
+				<Pallet<T>>::name().as_bytes(),
+				item,
+				&Blake2_128Concat::hash(&account.encode()),
+				records,
+			);
+		}
+		(lists.len() as u64, slots)
+	}
+}
+
+pub mod v6 {
+	use super::*;
+	use sp_runtime::traits::Saturating;
+
+	/// Record the block the ownership of every linked address was last proven at, which is its
+	/// linking block, and index the links in `LinkExpiry` when `LinkValidityPeriod` is set.
+	///
+	/// The sweep of stale links starts at the current block, the links that are already stale
+	/// are announced by its first run. The pending `link_eth_contract` requests are indexed
+	/// under their expiring block number for their sweep.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V5_0_0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let mut lists = 0u64;
+		let mut indexed = 0u64;
+
+		EthereumLink::<T>::translate::<Vec<v5::LinkRecord<EthAddress, T::BlockNumber>>, _>(
+			|account, links| {
+				lists += 1;
+				Some(records::<T, _, _>(
+					&account,
+					links,
+					LinkedAddress::Ethereum,
+					now,
+					&mut indexed,
+				))
+			},
+		);
+		BitcoinLink::<T>::translate::<Vec<v5::LinkRecord<Vec<u8>, T::BlockNumber>>, _>(
+			|account, links| {
+				lists += 1;
+				Some(records::<T, _, _>(&account, links, LinkedAddress::Bitcoin, now, &mut indexed))
+			},
+		);
+		TronLink::<T>::translate::<Vec<v5::LinkRecord<EthAddress, T::BlockNumber>>, _>(
+			|account, links| {
+				lists += 1;
+				Some(records::<T, _, _>(&account, links, LinkedAddress::Tron, now, &mut indexed))
+			},
+		);
+		SolanaLink::<T>::translate::<Vec<v5::LinkRecord<SolanaAddress, T::BlockNumber>>, _>(
+			|account, links| {
+				lists += 1;
+				Some(records::<T, _, _>(&account, links, LinkedAddress::Solana, now, &mut indexed))
+			},
+		);
+		CosmosLink::<T>::translate::<Vec<v5::LinkRecord<Vec<u8>, T::BlockNumber>>, _>(
+			|account, links| {
+				lists += 1;
+				Some(records::<T, _, _>(&account, links, LinkedAddress::Cosmos, now, &mut indexed))
+			},
+		);
+		PolkadotLink::<T>::translate::<Vec<v5::LinkRecord<T::AccountId, T::BlockNumber>>, _>(
+			|account, links| {
+				lists += 1;
+				Some(records::<T, _, _>(
+					&account,
+					links,
+					LinkedAddress::Polkadot,
+					now,
+					&mut indexed,
+				))
+			},
+		);
+
+		// the pending `link_eth_contract` requests are indexed by expiry from this release on, the
+		// sweep starts at the earliest of them
+		let mut requests = 0u64;
+		let mut sweep = now;
+		for (account, contract, request) in EthContractPending::<T>::iter() {
+			requests += 1;
+			sweep = sweep.min(request.expiring_block_number);
+			EthContractPendingExpiry::<T>::insert(
+				request.expiring_block_number,
+				(account, contract),
+				(),
+			);
+		}
+
+		LinkExpirySweep::<T>::put(now);
+		EthContractPendingSweep::<T>::put(sweep);
+		StorageVersion::<T>::put(Releases::V6_0_0);
+
+		log::info!(target: "account-linker", "indexed {} links to go stale in v6", indexed);
+
+		T::DbWeight::get().reads_writes(lists + requests + 1, lists + indexed + requests + 3)
+	}
+
+	// The v6 records of the v5 `links` of `account`, indexing them under the block they go stale
+	// at, or the current block if they already are
+	fn records<T: Config, A: Clone, S: Get<u32>>(
+		account: &T::AccountId,
+		links: Vec<v5::LinkRecord<A, T::BlockNumber>>,
+		key: fn(A) -> LinkedAddress<T::AccountId>,
+		now: T::BlockNumber,
+		indexed: &mut u64,
+	) -> BoundedVec<LinkRecord<A, T::BlockNumber>, S> {
+		let records = links
+			.into_iter()
+			.map(|link| {
+				if let Some(period) = T::LinkValidityPeriod::get() {
+					let stale_at = link.linked_at.saturating_add(period).max(now);
+					LinkExpiry::<T>::insert(stale_at, (account, key(link.address.clone())), ());
+					*indexed += 1;
+				}
+				LinkRecord {
+					address: link.address,
+					linked_at: link.linked_at,
+					verified_at: link.linked_at,
+					proof: link.proof,
+					label: link.label,
+				}
+			})
+			.collect();
+		truncate(records)
+	}
+}
@@ -1,17 +1,27 @@
 use crate as account_linker;
+use account_linker::{LinkMode, LinkedAddress, OnLinkChanged};
+use codec::Encode;
 use frame_support::{
-	parameter_types,
-	traits::{OnFinalize, OnInitialize},
+	assert_ok, parameter_types,
+	traits::{GenesisBuild, OnFinalize, OnInitialize, SortedMembers},
 };
 use frame_system as system;
+use parity_crypto::{
+	publickey::{sign, KeyPair, Message},
+	Keccak256,
+};
 use sp_core::H256;
+use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, IdentityLookup},
-	AccountId32,
+	AccountId32, MultiSignature, MultiSigner,
 };
+use std::sync::Arc;
+
+pub const MAX_ETH_LINKS: usize = 3;
 
-pub use crate::MAX_ETH_LINKS;
+pub const EXPIRING_BLOCK_NUMBER: u32 = 100;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -24,13 +34,37 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		AccountLinker: account_linker::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		AccountLinker: account_linker::{Pallet, Call, Storage, Config, Event<T>},
 	}
 );
 
 parameter_types! {
 	pub const BlockHashCount: u32 = 250;
 	pub const SS58Prefix: u8 = 42;
+	pub const EthChainId: u64 = 42;
+	pub static BtcNetwork: account_linker::BitcoinNetwork = account_linker::BitcoinNetwork::Mainnet;
+	pub static Exclusivity: account_linker::LinkExclusivity = account_linker::LinkExclusivity::Shared;
+	pub const MaxDisplacedAccounts: u32 = 2;
+	pub const MaxEthLinks: u32 = MAX_ETH_LINKS as u32;
+	pub const MaxBtcLinks: u32 = 3;
+	pub const MaxTronLinks: u32 = 3;
+	pub const MaxSolanaLinks: u32 = 3;
+	pub const MaxCosmosLinks: u32 = 3;
+	pub CosmosHrp: Vec<u8> = b"cosmos".to_vec();
+	pub const MaxPolkadotLinks: u32 = 3;
+	pub const ExpiringBlockNumberMax: u32 = 10 * 60 * 24 * 30; // 30 days for 6s per block
+	pub const PendingSweepLimit: u32 = 10;
+	pub static LinkDeposit: u128 = 0;
+	pub static PendingDeposit: u128 = 0;
+	pub static Attesters: Vec<AccountId32> = vec![];
+	pub static ContractLinkQuorum: u32 = 2;
+	pub const MaxContractSignatureLen: u32 = 1024;
+	pub const MaxLinkLabelLen: u32 = 32;
+	pub static LinkValidityPeriod: Option<u32> = None;
+	pub const StaleSweepLimit: u32 = 10;
+	pub static LinkChanges: Vec<LinkChange> = vec![];
+	pub const ExistentialDeposit: u128 = 1;
 }
 
 impl system::Config for Test {
@@ -51,7 +85,7 @@ impl system::Config for Test {
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u128>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -59,16 +93,153 @@ impl system::Config for Test {
 	type OnSetCode = ();
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u128;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
 impl account_linker::Config for Test {
 	type Event = Event;
 	type WeightInfo = ();
+	type Currency = Balances;
+	type LinkDeposit = LinkDeposit;
+	type PendingDeposit = PendingDeposit;
+	type LinkDomain = account_linker::GenesisHashAndPalletIndex<Test>;
+	type EthChainId = EthChainId;
+	type BitcoinNetwork = BtcNetwork;
+	type LinkExclusivity = Exclusivity;
+	type MaxDisplacedAccounts = MaxDisplacedAccounts;
+	type PolkadotSignature = MultiSignature;
+	type PolkadotSigner = MultiSigner;
+	type CosmosHrp = CosmosHrp;
+	type MaxEthLinks = MaxEthLinks;
+	type MaxBtcLinks = MaxBtcLinks;
+	type MaxTronLinks = MaxTronLinks;
+	type MaxSolanaLinks = MaxSolanaLinks;
+	type MaxCosmosLinks = MaxCosmosLinks;
+	type MaxPolkadotLinks = MaxPolkadotLinks;
+	type ExpiringBlockNumberMax = ExpiringBlockNumberMax;
+	type PendingSweepLimit = PendingSweepLimit;
+	type ContractLinkAttesters = ContractLinkAttesters;
+	type ContractLinkQuorum = ContractLinkQuorum;
+	type MaxContractSignatureLen = MaxContractSignatureLen;
+	type MaxLinkLabelLen = MaxLinkLabelLen;
+	type LinkValidityPeriod = LinkValidityPeriod;
+	type StaleSweepLimit = StaleSweepLimit;
+	type OnLinkChanged = RecordLinkChanges;
+}
+
+// The accounts set with `Attesters::set`
+pub struct ContractLinkAttesters;
+impl SortedMembers<AccountId32> for ContractLinkAttesters {
+	fn sorted_members() -> Vec<AccountId32> {
+		let mut attesters = Attesters::get();
+		attesters.sort();
+		attesters
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add(who: &AccountId32) {
+		let mut attesters = Attesters::get();
+		attesters.push(who.clone());
+		Attesters::set(&attesters);
+	}
+}
+
+// An account, an address and whether it was linked (or unlinked) to the account
+pub type LinkChange = (AccountId32, LinkedAddress<AccountId32>, bool);
+
+// Records the link changes in `LinkChanges`
+pub struct RecordLinkChanges;
+impl RecordLinkChanges {
+	fn record(change: LinkChange) {
+		let mut changes = LinkChanges::get();
+		changes.push(change);
+		LinkChanges::set(changes);
+	}
+}
+impl OnLinkChanged<AccountId32> for RecordLinkChanges {
+	fn on_linked(account: &AccountId32, addr: &LinkedAddress<AccountId32>) {
+		Self::record((account.clone(), addr.clone(), true));
+	}
+
+	fn on_unlinked(account: &AccountId32, addr: &LinkedAddress<AccountId32>) {
+		Self::record((account.clone(), addr.clone(), false));
+	}
+}
+
+// The link changes since the last call
+pub fn link_changes() -> Vec<LinkChange> {
+	let changes = LinkChanges::get();
+	LinkChanges::set(vec![]);
+	changes
 }
 
 pub type AccountLinkerError = account_linker::Error<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisBuild::<Test>::assimilate_storage(&account_linker::GenesisConfig::default(), &mut t)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// The benchmarks sign with keys generated in the keystore
+	ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+	ext
+}
+
+// The link domain of the mock runtime: the genesis hash set by `frame_system` followed by the
+// index of `AccountLinker` in `construct_runtime!`
+pub fn link_domain() -> Vec<u8> {
+	let mut domain = vec![69u8; 32];
+	domain.push(2);
+	domain
+}
+
+// The part of the link message after "Link Litentry: " for `account`, expiring at
+// `EXPIRING_BLOCK_NUMBER`
+pub fn link_payload(account: &AccountId32, nonce: u32) -> Vec<u8> {
+	let mut payload = link_domain();
+	payload.append(&mut account.encode());
+	payload.append(&mut EXPIRING_BLOCK_NUMBER.encode());
+	payload.append(&mut nonce.encode());
+	payload
+}
+
+// The personal_sign signature of `msg`, prefixed with its length as wallets do
+pub fn eth_personal_sign(key_pair: &KeyPair, msg: &[u8]) -> [u8; 65] {
+	let mut bytes = format!("\x19Ethereum Signed Message:\n{}", msg.len()).into_bytes();
+	bytes.extend_from_slice(msg);
+	sign(key_pair.secret(), &Message::from(bytes.keccak256()))
+		.unwrap()
+		.into_electrum()
+}
+
+// The personal_sign signature of the link message of `account`
+pub fn eth_link_sig(account: &AccountId32, key_pair: &KeyPair, nonce: u32) -> [u8; 65] {
+	let mut msg = b"Link Litentry: ".to_vec();
+	msg.append(&mut link_payload(account, nonce));
+	eth_personal_sign(key_pair, &msg)
+}
+
+// Links the address of `key_pair` to `account` with a personal_sign signature
+pub fn link_eth(account: &AccountId32, key_pair: &KeyPair, mode: LinkMode) {
+	let nonce = AccountLinker::link_nonce(account);
+	assert_ok!(AccountLinker::link_eth(
+		Origin::signed(account.clone()),
+		mode,
+		key_pair.address().to_fixed_bytes(),
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		eth_link_sig(account, key_pair, nonce)
+	));
 }
 
 pub fn run_to_block(n: u32) {
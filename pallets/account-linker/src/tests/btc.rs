@@ -1,16 +1,81 @@
 use crate::mock::*;
 
+use crate::{
+	btc::{bip322, witness::WitnessProgram},
+	BitcoinNetwork, LinkExclusivity, LinkMode,
+};
 use codec::Encode;
 use frame_support::{assert_noop, assert_ok};
-use parity_crypto::Keccak256;
+use k256::schnorr::SigningKey;
 use sp_runtime::AccountId32;
 
 use bitcoin::{
+	hashes::{sha256d, Hash},
 	network::constants::Network,
-	secp256k1::{rand::thread_rng, Message as BTCMessage, Secp256k1},
+	secp256k1::{rand::thread_rng, Message as BTCMessage, Secp256k1, SecretKey},
 	util::{address::Address, key},
 };
 
+// BIP-137 header bytes for recovery id 0
+const HEADER_P2PKH_UNCOMPRESSED: u8 = 27;
+const HEADER_P2PKH_COMPRESSED: u8 = 31;
+const HEADER_P2SH_P2WPKH: u8 = 35;
+const HEADER_P2WPKH: u8 = 39;
+
+fn sign_link_message(
+	secret: &SecretKey,
+	account: &AccountId32,
+	block_number: u32,
+	nonce: u32,
+	header: u8,
+) -> Vec<u8> {
+	let mut payload = link_domain();
+	payload.append(&mut account.encode());
+	payload.append(&mut block_number.encode());
+	payload.append(&mut nonce.encode());
+	let msg = format!("Link Litentry: {}", hex::encode(payload));
+
+	let mut bytes = b"\x18Bitcoin Signed Message:\n".to_vec();
+	bytes.push(msg.len() as u8);
+	bytes.extend_from_slice(msg.as_bytes());
+
+	let message = BTCMessage::from_slice(&sha256d::Hash::hash(&bytes).into_inner()).unwrap();
+	let (v, rs) = Secp256k1::new().sign_recoverable(&message, secret).serialize_compact();
+
+	let mut sig = [0u8; 65];
+	sig[0] = header + v.to_i32() as u8;
+	sig[1..].copy_from_slice(&rs[..]);
+	sig.to_vec()
+}
+
+// BIP-322 simple signature, i.e. the encoded witness stack holding a single Schnorr signature
+fn sign_taproot_link_message(
+	secret: &SigningKey,
+	account: &AccountId32,
+	block_number: u32,
+	nonce: u32,
+) -> Vec<u8> {
+	let mut payload = link_domain();
+	payload.append(&mut account.encode());
+	payload.append(&mut block_number.encode());
+	payload.append(&mut nonce.encode());
+	let msg = format!("Link Litentry: {}", hex::encode(payload));
+
+	let mut output_key = [0u8; 32];
+	output_key.copy_from_slice(&secret.verifying_key().to_bytes());
+	let sighash = bip322::signature_hash(&output_key, msg.as_bytes(), bip322::SIGHASH_DEFAULT);
+	let sig = secret.try_sign_prehashed(&sighash, &[0u8; 32]).unwrap();
+
+	let mut witness = vec![1, 64];
+	witness.extend_from_slice(sig.as_bytes());
+	witness
+}
+
+fn taproot_address(secret: &SigningKey) -> Vec<u8> {
+	let wp = WitnessProgram { version: 1, program: secret.verifying_key().to_bytes().to_vec() };
+	wp.to_address(b"bc".to_vec()).unwrap()
+}
+
 #[test]
 fn test_invalid_expiring_block_number_btc() {
 	new_test_ext().execute_with(|| {
@@ -23,30 +88,17 @@ fn test_invalid_expiring_block_number_btc() {
 		let address = Address::p2pkh(&public_key, Network::Bitcoin);
 
 		let account: AccountId32 = AccountId32::from([255u8; 32]);
-		let block_number: u32 = crate::EXPIRING_BLOCK_NUMBER_MAX + 1;
-
-		let mut bytes = b"Link Litentry: ".encode();
-		let mut account_vec = account.encode();
-		let mut expiring_block_number_vec = block_number.encode();
-
-		bytes.append(&mut account_vec);
-		bytes.append(&mut expiring_block_number_vec);
-
-		let message = BTCMessage::from_slice(&bytes.keccak256()).unwrap();
-
-		let (v, rs) = s.sign_recoverable(&message, &pair.0).serialize_compact();
+		let block_number: u32 = ExpiringBlockNumberMax::get() + 1;
 
-		let mut sig = [0u8; 65];
-		sig[..64].copy_from_slice(&rs[..]);
-		sig[64] = v.to_i32() as u8;
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2PKH_COMPRESSED);
 
 		assert_noop!(
 			AccountLinker::link_btc(
 				Origin::signed(account.clone()),
-				account.clone(),
-				0,
+				LinkMode::Append,
 				address.clone().to_string().as_bytes().to_vec(),
 				block_number,
+				0,
 				sig
 			),
 			AccountLinkerError::InvalidExpiringBlockNumber
@@ -70,29 +122,16 @@ fn test_btc_link_p2pkh() {
 		let account: AccountId32 = AccountId32::from([255u8; 32]);
 		let block_number: u32 = 99999;
 
-		let mut bytes = b"Link Litentry: ".encode();
-		let mut account_vec = account.encode();
-		let mut expiring_block_number_vec = block_number.encode();
-
-		bytes.append(&mut account_vec);
-		bytes.append(&mut expiring_block_number_vec);
-
-		let message = BTCMessage::from_slice(&bytes.keccak256()).unwrap();
-
-		let (v, rs) = s.sign_recoverable(&message, &pair.0).serialize_compact();
-
-		let mut sig = [0u8; 65];
-		sig[..64].copy_from_slice(&rs[..]);
-		sig[64] = v.to_i32() as u8;
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2PKH_COMPRESSED);
 
 		let addr_expected = address.clone().to_string().as_bytes().to_vec();
 
 		assert_ok!(AccountLinker::link_btc(
 			Origin::signed(account.clone()),
-			account.clone(),
-			0,
+			LinkMode::Append,
 			addr_expected.clone(),
 			block_number,
+			0,
 			sig
 		));
 
@@ -121,33 +160,61 @@ fn test_btc_link_p2wpkh() {
 		// Generate pay-to-pubkey-hash address
 		let address = Address::p2wpkh(&public_key, Network::Bitcoin).unwrap();
 
-		println!("{}", address);
 		let account: AccountId32 = AccountId32::from([255u8; 32]);
 		let block_number: u32 = 99999;
 
-		let mut bytes = b"Link Litentry: ".encode();
-		let mut account_vec = account.encode();
-		let mut expiring_block_number_vec = block_number.encode();
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2WPKH);
+
+		let addr_expected = address.clone().to_string().as_bytes().to_vec();
+
+		assert_ok!(AccountLinker::link_btc(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr_expected.clone(),
+			block_number,
+			0,
+			sig
+		));
+
+		let addr_stored =
+			String::from_utf8(AccountLinker::btc_addresses(&account)[0].clone()).unwrap();
+
+		assert_eq!(addr_stored, address.to_string());
+
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::BtcAddressLinked(account.clone(), addr_expected)),]
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_p2sh_p2wpkh() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
 
-		bytes.append(&mut account_vec);
-		bytes.append(&mut expiring_block_number_vec);
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
 
-		let message = BTCMessage::from_slice(&bytes.keccak256()).unwrap();
+		// Generate nested segwit (P2SH-P2WPKH) address
+		let address = Address::p2shwpkh(&public_key, Network::Bitcoin).unwrap();
 
-		let (v, rs) = s.sign_recoverable(&message, &pair.0).serialize_compact();
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
 
-		let mut sig = [0u8; 65];
-		sig[..64].copy_from_slice(&rs[..]);
-		sig[64] = v.to_i32() as u8;
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2SH_P2WPKH);
 
 		let addr_expected = address.clone().to_string().as_bytes().to_vec();
+		assert_eq!(addr_expected[0], b'3');
 
 		assert_ok!(AccountLinker::link_btc(
 			Origin::signed(account.clone()),
-			account.clone(),
-			0,
+			LinkMode::Append,
 			addr_expected.clone(),
 			block_number,
+			0,
 			sig
 		));
 
@@ -162,3 +229,444 @@ fn test_btc_link_p2wpkh() {
 		);
 	});
 }
+
+#[test]
+fn test_btc_link_p2pkh_uncompressed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: false, key: pair.1 };
+
+		// Generate pay-to-pubkey-hash address from the uncompressed public key
+		let address = Address::p2pkh(&public_key, Network::Bitcoin);
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2PKH_UNCOMPRESSED);
+
+		let addr_expected = address.clone().to_string().as_bytes().to_vec();
+
+		assert_ok!(AccountLinker::link_btc(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr_expected.clone(),
+			block_number,
+			0,
+			sig
+		));
+
+		assert_eq!(AccountLinker::btc_addresses(&account), vec![addr_expected]);
+	});
+}
+
+#[test]
+fn test_btc_link_p2wpkh_with_p2pkh_header() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+
+		let address = Address::p2wpkh(&public_key, Network::Bitcoin).unwrap();
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		// Older wallets sign for segwit addresses with the compressed P2PKH header
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2PKH_COMPRESSED);
+
+		let addr_expected = address.clone().to_string().as_bytes().to_vec();
+
+		assert_ok!(AccountLinker::link_btc(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr_expected.clone(),
+			block_number,
+			0,
+			sig
+		));
+
+		assert_eq!(AccountLinker::btc_addresses(&account), vec![addr_expected]);
+	});
+}
+
+#[test]
+fn test_btc_link_unexpected_signature_type() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+
+		let address = Address::p2pkh(&public_key, Network::Bitcoin);
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2WPKH);
+
+		assert_noop!(
+			AccountLinker::link_btc(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				address.to_string().as_bytes().to_vec(),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::UnexpectedBTCSignatureType
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_invalid_signature_header() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+
+		let address = Address::p2pkh(&public_key, Network::Bitcoin);
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut sig =
+			sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2PKH_COMPRESSED);
+		sig[0] = 0;
+
+		assert_noop!(
+			AccountLinker::link_btc(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				address.to_string().as_bytes().to_vec(),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::InvalidBTCSignature
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_p2tr() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let secret = SigningKey::from_bytes(&[1u8; 32]).unwrap();
+		let addr_expected = taproot_address(&secret);
+		assert!(addr_expected.starts_with(b"bc1p"));
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let sig = sign_taproot_link_message(&secret, &account, block_number, 0);
+
+		assert_ok!(AccountLinker::link_btc(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr_expected.clone(),
+			block_number,
+			0,
+			sig
+		));
+
+		assert_eq!(AccountLinker::btc_addresses(&account), vec![addr_expected.clone()]);
+
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::BtcAddressLinked(account.clone(), addr_expected)),]
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_p2tr_wrong_key() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let secret = SigningKey::from_bytes(&[1u8; 32]).unwrap();
+		let other_secret = SigningKey::from_bytes(&[2u8; 32]).unwrap();
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let sig = sign_taproot_link_message(&other_secret, &account, block_number, 0);
+
+		assert_noop!(
+			AccountLinker::link_btc(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				taproot_address(&secret),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::SchnorrVerifyFailure
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_p2tr_bip137_signature() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let secret = SigningKey::from_bytes(&[1u8; 32]).unwrap();
+
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		// A BIP-137 signature is no valid witness stack
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2WPKH);
+
+		assert_noop!(
+			AccountLinker::link_btc(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				taproot_address(&secret),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::SchnorrVerifyFailure
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_testnet_addresses() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let cases = vec![
+			(BitcoinNetwork::Testnet, Network::Testnet),
+			(BitcoinNetwork::Signet, Network::Testnet),
+			(BitcoinNetwork::Regtest, Network::Regtest),
+		];
+		for (network, btc_network) in cases {
+			BtcNetwork::set(&network);
+
+			// Generate random key pair
+			let s = Secp256k1::new();
+			let pair = s.generate_keypair(&mut thread_rng());
+			let public_key = key::PublicKey { compressed: true, key: pair.1 };
+
+			let addresses = vec![
+				(Address::p2pkh(&public_key, btc_network), HEADER_P2PKH_COMPRESSED),
+				(Address::p2shwpkh(&public_key, btc_network).unwrap(), HEADER_P2SH_P2WPKH),
+				(Address::p2wpkh(&public_key, btc_network).unwrap(), HEADER_P2WPKH),
+			];
+			for (address, header) in addresses {
+				let nonce = AccountLinker::link_nonce(&account);
+				let sig = sign_link_message(&pair.0, &account, block_number, nonce, header);
+				let addr_expected = address.to_string().as_bytes().to_vec();
+
+				assert_ok!(AccountLinker::link_btc(
+					Origin::signed(account.clone()),
+					LinkMode::Append,
+					addr_expected.clone(),
+					block_number,
+					nonce,
+					sig
+				));
+				assert_eq!(AccountLinker::btc_addresses(&account)[0], addr_expected);
+			}
+		}
+	});
+}
+
+#[test]
+fn test_btc_link_other_network_address() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		// The mock runtime only accepts mainnet addresses
+		let addresses = vec![
+			(Address::p2pkh(&public_key, Network::Testnet), HEADER_P2PKH_COMPRESSED),
+			(Address::p2wpkh(&public_key, Network::Regtest).unwrap(), HEADER_P2WPKH),
+		];
+		for (address, header) in addresses {
+			let sig = sign_link_message(&pair.0, &account, block_number, 0, header);
+
+			assert_noop!(
+				AccountLinker::link_btc(
+					Origin::signed(account.clone()),
+					LinkMode::Append,
+					address.to_string().as_bytes().to_vec(),
+					block_number,
+					0,
+					sig
+				),
+				AccountLinkerError::InvalidBTCAddress
+			);
+		}
+	});
+}
+
+#[test]
+fn test_btc_link_by_third_party() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+		let addr_expected = Address::p2pkh(&public_key, Network::Bitcoin).to_string().into_bytes();
+
+		let victim: AccountId32 = AccountId32::from([1u8; 32]);
+		let mallory: AccountId32 = AccountId32::from([2u8; 32]);
+		let block_number: u32 = 99999;
+		LinkDeposit::set(&10);
+		Balances::make_free_balance_be(&victim, 100);
+
+		// The signature of the victim's link message is only valid for the victim's origin
+		let sig = sign_link_message(&pair.0, &victim, block_number, 0, HEADER_P2PKH_COMPRESSED);
+		assert_noop!(
+			AccountLinker::link_btc(
+				Origin::signed(mallory.clone()),
+				LinkMode::Append,
+				addr_expected.clone(),
+				block_number,
+				0,
+				sig.clone()
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+		assert!(AccountLinker::btc_addresses(&victim).is_empty());
+		assert!(AccountLinker::btc_addresses(&mallory).is_empty());
+		assert_eq!(AccountLinker::link_nonce(&victim), 0);
+		assert_eq!(Balances::reserved_balance(&victim), 0);
+
+		assert_ok!(AccountLinker::link_btc(
+			Origin::signed(victim.clone()),
+			LinkMode::Append,
+			addr_expected.clone(),
+			block_number,
+			0,
+			sig
+		));
+		assert_eq!(AccountLinker::btc_addresses(&victim), vec![addr_expected]);
+		assert_eq!(Balances::reserved_balance(&victim), 10);
+	});
+}
+
+#[test]
+fn test_btc_unlink() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		// Generate random key pair
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+
+		// Generate pay-to-pubkey-hash address
+		let address = Address::p2pkh(&public_key, Network::Bitcoin);
+
+		let account: AccountId32 = AccountId32::from([255u8; 32]);
+		let block_number: u32 = 99999;
+
+		let sig = sign_link_message(&pair.0, &account, block_number, 0, HEADER_P2PKH_COMPRESSED);
+
+		let addr_expected = address.clone().to_string().as_bytes().to_vec();
+
+		assert_ok!(AccountLinker::link_btc(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr_expected.clone(),
+			block_number,
+			0,
+			sig
+		));
+		events();
+		assert_eq!(AccountLinker::btc_linked_accounts(&addr_expected), vec![account.clone()]);
+
+		assert_ok!(AccountLinker::unlink_btc(
+			Origin::signed(account.clone()),
+			addr_expected.clone()
+		));
+		assert!(AccountLinker::btc_addresses(&account).is_empty());
+		assert!(AccountLinker::btc_linked_accounts(&addr_expected).is_empty());
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::BtcAddressUnlinked(
+				account.clone(),
+				addr_expected.clone()
+			)),]
+		);
+
+		assert_noop!(
+			AccountLinker::unlink_btc(Origin::signed(account.clone()), addr_expected),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
+
+#[test]
+fn test_btc_link_exclusivity_transfer() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		Exclusivity::set(&LinkExclusivity::Transfer);
+
+		let s = Secp256k1::new();
+		let pair = s.generate_keypair(&mut thread_rng());
+		let public_key = key::PublicKey { compressed: true, key: pair.1 };
+		let addr_expected = Address::p2wpkh(&public_key, Network::Bitcoin)
+			.unwrap()
+			.to_string()
+			.as_bytes()
+			.to_vec();
+
+		let alice: AccountId32 = AccountId32::from([1u8; 32]);
+		let bob: AccountId32 = AccountId32::from([2u8; 32]);
+		let block_number: u32 = 99999;
+
+		for account in &[alice.clone(), bob.clone()] {
+			let sig = sign_link_message(&pair.0, account, block_number, 0, HEADER_P2WPKH);
+			assert_ok!(AccountLinker::link_btc(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				addr_expected.clone(),
+				block_number,
+				0,
+				sig
+			));
+		}
+
+		assert!(events().contains(&Event::AccountLinker(crate::Event::BtcAddressTransferred(
+			alice.clone(),
+			bob.clone(),
+			addr_expected.clone()
+		))));
+		assert!(AccountLinker::btc_addresses(&alice).is_empty());
+		assert_eq!(AccountLinker::btc_addresses(&bob), vec![addr_expected.clone()]);
+		assert_eq!(AccountLinker::btc_linked_accounts(&addr_expected), vec![bob]);
+	});
+}
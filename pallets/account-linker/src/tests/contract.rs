@@ -0,0 +1,358 @@
+use crate::{mock::*, EthContractPendingExpiry, LinkExclusivity, LinkMode, LinkProof};
+
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, traits::Currency};
+use sp_runtime::AccountId32;
+
+const EXPIRING_BLOCK_NUMBER: u32 = 100;
+
+const CONTRACT: [u8; 20] = [7u8; 20];
+
+fn attesters() -> [AccountId32; 3] {
+	let attesters = [
+		AccountId32::from([10u8; 32]),
+		AccountId32::from([11u8; 32]),
+		AccountId32::from([12u8; 32]),
+	];
+	Attesters::set(&attesters.to_vec());
+	attesters
+}
+
+fn link_eth_contract(account: &AccountId32) {
+	let nonce = AccountLinker::link_nonce(account);
+	assert_ok!(AccountLinker::link_eth_contract(
+		Origin::signed(account.clone()),
+		LinkMode::Append,
+		CONTRACT,
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		vec![1u8; 65]
+	));
+}
+
+#[test]
+fn test_link_eth_contract_request() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		PendingDeposit::set(&3);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		Balances::make_free_balance_be(&account, 100);
+
+		link_eth_contract(&account);
+
+		let request = AccountLinker::eth_contract_pending(&account, &CONTRACT).unwrap();
+		let mut bytes = b"Link Litentry: ".to_vec();
+		bytes.append(&mut link_domain());
+		bytes.append(&mut account.encode());
+		bytes.append(&mut EXPIRING_BLOCK_NUMBER.encode());
+		bytes.append(&mut 0u32.encode());
+		assert_eq!(request.hash, crate::util_eth::eth_data_hash(bytes));
+		assert_eq!(request.signature.to_vec(), vec![1u8; 65]);
+		assert!(AccountLinker::eth_addresses(&account).is_empty());
+		assert_eq!(AccountLinker::link_nonce(&account), 1);
+		assert_eq!(Balances::reserved_balance(&account), 3);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthContractLinkRequested(
+				account.clone(),
+				CONTRACT.to_vec()
+			)),]
+		);
+
+		// Renewing the request doesn't take a second deposit
+		link_eth_contract(&account);
+		assert_eq!(Balances::reserved_balance(&account), 3);
+		assert_eq!(AccountLinker::eth_contract_pending_requests().len(), 1);
+	});
+}
+
+#[test]
+fn test_link_eth_contract_signature_too_long() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		assert_noop!(
+			AccountLinker::link_eth_contract(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				CONTRACT,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				vec![1u8; MaxContractSignatureLen::get() as usize + 1]
+			),
+			AccountLinkerError::ContractSignatureTooLong
+		);
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_quorum() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		PendingDeposit::set(&3);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		Balances::make_free_balance_be(&account, 100);
+		let [alice, bob, _] = attesters();
+
+		link_eth_contract(&account);
+		events();
+
+		assert_ok!(AccountLinker::attest_eth_contract_link(
+			alice.clone(),
+			account.clone(),
+			CONTRACT,
+			true
+		));
+		assert!(AccountLinker::eth_addresses(&account).is_empty());
+		assert_eq!(
+			AccountLinker::eth_contract_pending(&account, &CONTRACT)
+				.unwrap()
+				.approvals
+				.to_vec(),
+			vec![alice.clone()]
+		);
+
+		assert_ok!(AccountLinker::attest_eth_contract_link(bob, account.clone(), CONTRACT, true));
+		assert_eq!(AccountLinker::eth_addresses(&account), vec![CONTRACT]);
+		assert_eq!(AccountLinker::eth_links(&account)[0].proof, LinkProof::Eip1271);
+		assert_eq!(AccountLinker::eth_linked_accounts(&CONTRACT), vec![account.clone()]);
+		assert_eq!(AccountLinker::eth_contract_pending(&account, &CONTRACT), None);
+		assert_eq!(Balances::reserved_balance(&account), 0);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthAddressLinked(
+				account.clone(),
+				CONTRACT.to_vec()
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_transfer() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let holder: AccountId32 = AccountId32::from([1u8; 32]);
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let [alice, bob, _] = attesters();
+
+		link_eth_contract(&holder);
+		link_eth_contract(&account);
+		assert_eq!(
+			AccountLinker::attest_eth_contract_link(alice.clone(), holder.clone(), CONTRACT, true),
+			Ok(0)
+		);
+		assert_eq!(
+			AccountLinker::attest_eth_contract_link(bob.clone(), holder.clone(), CONTRACT, true),
+			Ok(0)
+		);
+		Exclusivity::set(&LinkExclusivity::Transfer);
+
+		// the attestation that links the contract returns the accounts it was transferred from
+		assert_ok!(AccountLinker::attest_eth_contract_link(alice, account.clone(), CONTRACT, true));
+		assert_eq!(
+			AccountLinker::attest_eth_contract_link(bob, account.clone(), CONTRACT, true),
+			Ok(1)
+		);
+		assert_eq!(AccountLinker::eth_linked_accounts(&CONTRACT), vec![account]);
+		assert!(AccountLinker::eth_addresses(&holder).is_empty());
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_quorum_lowered() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let [alice, bob, _] = attesters();
+
+		link_eth_contract(&account);
+		assert_ok!(AccountLinker::attest_eth_contract_link(alice, account.clone(), CONTRACT, true));
+
+		// The approvals already reach the new quorum
+		ContractLinkQuorum::set(&1);
+		assert_ok!(AccountLinker::attest_eth_contract_link(bob, account.clone(), CONTRACT, true));
+		assert_eq!(AccountLinker::eth_addresses(&account), vec![CONTRACT]);
+		assert_eq!(AccountLinker::eth_contract_pending(&account, &CONTRACT), None);
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_rejected() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let [alice, bob, carol] = attesters();
+
+		link_eth_contract(&account);
+		events();
+
+		assert_ok!(AccountLinker::attest_eth_contract_link(
+			alice,
+			account.clone(),
+			CONTRACT,
+			false
+		));
+		// a split vote doesn't settle the request
+		assert_ok!(AccountLinker::attest_eth_contract_link(bob, account.clone(), CONTRACT, true));
+		assert!(AccountLinker::eth_contract_pending(&account, &CONTRACT).is_some());
+
+		assert_ok!(AccountLinker::attest_eth_contract_link(
+			carol,
+			account.clone(),
+			CONTRACT,
+			false
+		));
+		assert_eq!(AccountLinker::eth_contract_pending(&account, &CONTRACT), None);
+		assert!(AccountLinker::eth_addresses(&account).is_empty());
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthContractLinkRejected(
+				account.clone(),
+				CONTRACT.to_vec()
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_not_attester() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		attesters();
+
+		link_eth_contract(&account);
+		assert_noop!(
+			AccountLinker::attest_eth_contract_link(
+				AccountId32::from([1u8; 32]),
+				account.clone(),
+				CONTRACT,
+				true
+			),
+			AccountLinkerError::NotContractLinkAttester
+		);
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_twice() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let [alice, _, _] = attesters();
+
+		link_eth_contract(&account);
+		assert_ok!(AccountLinker::attest_eth_contract_link(
+			alice.clone(),
+			account.clone(),
+			CONTRACT,
+			true
+		));
+		assert_noop!(
+			AccountLinker::attest_eth_contract_link(alice, account.clone(), CONTRACT, false),
+			AccountLinkerError::AlreadyAttested
+		);
+	});
+}
+
+#[test]
+fn test_attest_eth_contract_link_expired() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		PendingDeposit::set(&3);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		Balances::make_free_balance_be(&account, 100);
+		let [alice, _, _] = attesters();
+
+		link_eth_contract(&account);
+		events();
+		// before the sweep reaches the request
+		System::set_block_number(EXPIRING_BLOCK_NUMBER);
+
+		assert_ok!(AccountLinker::attest_eth_contract_link(alice, account.clone(), CONTRACT, true));
+		assert_eq!(AccountLinker::eth_contract_pending(&account, &CONTRACT), None);
+		assert!(AccountLinker::eth_addresses(&account).is_empty());
+		assert_eq!(Balances::reserved_balance(&account), 0);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthContractLinkExpired(
+				account.clone(),
+				CONTRACT.to_vec()
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_sweep_expired_eth_contract_pending() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		PendingDeposit::set(&3);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		Balances::make_free_balance_be(&account, 100);
+
+		link_eth_contract(&account);
+		// renewing the request moves its expiry
+		let nonce = AccountLinker::link_nonce(&account);
+		assert_ok!(AccountLinker::link_eth_contract(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			CONTRACT,
+			EXPIRING_BLOCK_NUMBER + 10,
+			nonce,
+			vec![1u8; 65]
+		));
+		events();
+
+		run_to_block(EXPIRING_BLOCK_NUMBER);
+		assert!(AccountLinker::eth_contract_pending(&account, &CONTRACT).is_some());
+		assert!(events().is_empty());
+
+		run_to_block(EXPIRING_BLOCK_NUMBER + 10);
+		assert_eq!(AccountLinker::eth_contract_pending(&account, &CONTRACT), None);
+		assert_eq!(EthContractPendingExpiry::<Test>::iter().count(), 0);
+		assert_eq!(Balances::reserved_balance(&account), 0);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthContractLinkExpired(
+				account.clone(),
+				CONTRACT.to_vec()
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_cancel_eth_contract_link() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		PendingDeposit::set(&3);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		Balances::make_free_balance_be(&account, 100);
+
+		link_eth_contract(&account);
+		assert_ok!(AccountLinker::cancel_eth_contract_link(
+			Origin::signed(account.clone()),
+			CONTRACT
+		));
+		assert_eq!(AccountLinker::eth_contract_pending(&account, &CONTRACT), None);
+		assert_eq!(Balances::reserved_balance(&account), 0);
+
+		assert_noop!(
+			AccountLinker::cancel_eth_contract_link(Origin::signed(account.clone()), CONTRACT),
+			AccountLinkerError::WrongPendingRequest
+		);
+	});
+}
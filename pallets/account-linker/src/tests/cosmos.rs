@@ -0,0 +1,191 @@
+use crate::{cosmos::to_base64, mock::*, LinkMode};
+
+use bitcoin::{
+	bech32::{self, ToBase32},
+	hashes::{hash160, sha256, Hash},
+	secp256k1::{Message, PublicKey, Secp256k1, SecretKey},
+};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::AccountId32;
+
+const EXPIRING_BLOCK_NUMBER: u32 = 100;
+
+fn cosmos_address(hrp: &str, secret: &SecretKey) -> Vec<u8> {
+	let pk = PublicKey::from_secret_key(&Secp256k1::new(), secret).serialize();
+	let hash = hash160::Hash::hash(&pk);
+	bech32::encode(hrp, hash.to_base32()).unwrap().into_bytes()
+}
+
+// The signature Keplr's `signArbitrary` returns for the link message
+fn sign_link_message(
+	secret: &SecretKey,
+	signer: &[u8],
+	account: &AccountId32,
+	block_number: u32,
+	nonce: u32,
+) -> [u8; 64] {
+	let mut payload = link_domain();
+	payload.append(&mut account.encode());
+	payload.append(&mut block_number.encode());
+	payload.append(&mut nonce.encode());
+	let msg = format!("Link Litentry: {}", hex::encode(payload));
+
+	let doc = format!(
+		r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+		String::from_utf8(to_base64(msg.as_bytes())).unwrap(),
+		String::from_utf8(signer.to_vec()).unwrap(),
+	);
+
+	let message = Message::from_slice(&sha256::Hash::hash(doc.as_bytes()).into_inner()).unwrap();
+	Secp256k1::new().sign(&message, secret).serialize_compact()
+}
+
+fn link_cosmos(account: &AccountId32, secret: &SecretKey, addr: &[u8]) {
+	let nonce = AccountLinker::link_nonce(account);
+	assert_ok!(AccountLinker::link_cosmos(
+		Origin::signed(account.clone()),
+		LinkMode::Append,
+		addr.to_vec(),
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		sign_link_message(secret, addr, account, EXPIRING_BLOCK_NUMBER, nonce)
+	));
+}
+
+#[test]
+fn test_cosmos_link() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let secret = SecretKey::from_slice(&[1u8; 32]).unwrap();
+		let addr = cosmos_address("cosmos", &secret);
+
+		link_cosmos(&account, &secret, &addr);
+
+		assert_eq!(AccountLinker::cosmos_addresses(&account), vec![addr.clone()]);
+		assert_eq!(AccountLinker::cosmos_linked_accounts(&addr), vec![account.clone()]);
+		assert_eq!(AccountLinker::link_nonce(&account), 1);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::CosmosAddressLinked(account.clone(), addr)),]
+		);
+	});
+}
+
+#[test]
+fn test_cosmos_link_recovery_ids() {
+	// The link signature of the first key has recovery id 0, the one of the second key 1
+	for seed in &[1u8, 9] {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			let account: AccountId32 = AccountId32::from([0u8; 32]);
+			let secret = SecretKey::from_slice(&[*seed; 32]).unwrap();
+			let addr = cosmos_address("cosmos", &secret);
+
+			link_cosmos(&account, &secret, &addr);
+			assert!(AccountLinker::is_cosmos_linked(&addr, &account));
+		});
+	}
+}
+
+#[test]
+fn test_cosmos_link_other_hrp() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let secret = SecretKey::from_slice(&[1u8; 32]).unwrap();
+		let addr = cosmos_address("osmo", &secret);
+
+		assert_noop!(
+			AccountLinker::link_cosmos(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				addr.clone(),
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sign_link_message(&secret, &addr, &account, EXPIRING_BLOCK_NUMBER, 0)
+			),
+			AccountLinkerError::InvalidCosmosAddress
+		);
+	});
+}
+
+#[test]
+fn test_cosmos_link_unexpected_address() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let secret = SecretKey::from_slice(&[1u8; 32]).unwrap();
+		let other = SecretKey::from_slice(&[2u8; 32]).unwrap();
+		let addr = cosmos_address("cosmos", &other);
+
+		assert_noop!(
+			AccountLinker::link_cosmos(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				addr.clone(),
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sign_link_message(&secret, &addr, &account, EXPIRING_BLOCK_NUMBER, 0)
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+	});
+}
+
+#[test]
+fn test_cosmos_link_by_third_party() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let victim: AccountId32 = AccountId32::from([1u8; 32]);
+		let mallory: AccountId32 = AccountId32::from([2u8; 32]);
+		let secret = SecretKey::from_slice(&[1u8; 32]).unwrap();
+		let addr = cosmos_address("cosmos", &secret);
+		LinkDeposit::set(&10);
+		Balances::make_free_balance_be(&victim, 100);
+
+		// The signature of the victim's link message is only valid for the victim's origin
+		assert_noop!(
+			AccountLinker::link_cosmos(
+				Origin::signed(mallory.clone()),
+				LinkMode::Append,
+				addr.clone(),
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sign_link_message(&secret, &addr, &victim, EXPIRING_BLOCK_NUMBER, 0)
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+		assert!(AccountLinker::cosmos_addresses(&victim).is_empty());
+		assert!(AccountLinker::cosmos_addresses(&mallory).is_empty());
+		assert_eq!(AccountLinker::link_nonce(&victim), 0);
+		assert_eq!(Balances::reserved_balance(&victim), 0);
+	});
+}
+
+#[test]
+fn test_cosmos_unlink() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let secret = SecretKey::from_slice(&[1u8; 32]).unwrap();
+		let addr = cosmos_address("cosmos", &secret);
+
+		link_cosmos(&account, &secret, &addr);
+		assert_ok!(AccountLinker::unlink_cosmos(Origin::signed(account.clone()), addr.clone()));
+
+		assert!(AccountLinker::cosmos_addresses(&account).is_empty());
+		assert!(!AccountLinker::is_cosmos_linked(&addr, &account));
+		assert_noop!(
+			AccountLinker::unlink_cosmos(Origin::signed(account.clone()), addr),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
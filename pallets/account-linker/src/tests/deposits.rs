@@ -0,0 +1,140 @@
+use crate::{mock::*, Deposits, LinkMode, LinkProof, LinkRecord, PolkadotLink};
+
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, ReservableCurrency},
+	BoundedVec,
+};
+use sp_runtime::AccountId32;
+use sp_std::convert::TryFrom;
+
+const EXPIRING_BLOCK_NUMBER: u32 = 100;
+
+fn setup_deposits(origin: &AccountId32) {
+	LinkDeposit::set(&10);
+	PendingDeposit::set(&3);
+	Balances::make_free_balance_be(origin, 100);
+}
+
+fn link_polkadot(origin: &AccountId32, target: &AccountId32, mode: LinkMode) {
+	assert_ok!(AccountLinker::link_polkadot(
+		Origin::signed(origin.clone()),
+		target.clone(),
+		mode,
+		EXPIRING_BLOCK_NUMBER
+	));
+}
+
+#[test]
+fn test_pending_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+		setup_deposits(&origin);
+
+		link_polkadot(&origin, &target, LinkMode::Append);
+		assert_eq!(Balances::reserved_balance(&origin), 3);
+
+		// Renewing the request doesn't take a second deposit
+		link_polkadot(&origin, &target, LinkMode::Append);
+		assert_eq!(Balances::reserved_balance(&origin), 3);
+		assert_eq!(AccountLinker::deposits(&origin), Some(3));
+
+		assert_ok!(AccountLinker::cancel_polkadot_link(
+			Origin::signed(origin.clone()),
+			target.clone()
+		));
+		assert_eq!(Balances::reserved_balance(&origin), 0);
+		assert_eq!(AccountLinker::deposits(&origin), None);
+
+		link_polkadot(&origin, &target, LinkMode::Append);
+		run_to_block(EXPIRING_BLOCK_NUMBER);
+		assert_eq!(AccountLinker::polkadot_pending(&origin, &target), None);
+		assert_eq!(Balances::reserved_balance(&origin), 0);
+	});
+}
+
+#[test]
+fn test_link_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let alice: AccountId32 = AccountId32::from([1u8; 32]);
+		let bob: AccountId32 = AccountId32::from([2u8; 32]);
+		setup_deposits(&origin);
+
+		// The pending deposit is returned once the link deposit is taken
+		link_polkadot(&origin, &alice, LinkMode::Append);
+		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(alice.clone()), origin.clone()));
+		assert_eq!(Balances::reserved_balance(&origin), 10);
+
+		// Replacing a linked account reuses the deposit of its slot
+		link_polkadot(&origin, &bob, LinkMode::Replace(0));
+		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(bob.clone()), origin.clone()));
+		assert_eq!(Balances::reserved_balance(&origin), 10);
+
+		assert_ok!(AccountLinker::unlink_polkadot(Origin::signed(origin.clone()), bob));
+		assert_eq!(Balances::reserved_balance(&origin), 0);
+		assert_eq!(Balances::free_balance(&origin), 100);
+	});
+}
+
+#[test]
+fn test_link_deposit_insufficient_balance() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+		setup_deposits(&origin);
+
+		link_polkadot(&origin, &target, LinkMode::Append);
+		Balances::make_free_balance_be(&origin, 5);
+
+		assert_noop!(
+			AccountLinker::accept_polkadot(Origin::signed(target.clone()), origin.clone()),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert!(AccountLinker::polkadot_addresses(&origin).is_empty());
+
+		Balances::make_free_balance_be(&target, 1);
+		assert_noop!(
+			AccountLinker::link_polkadot(
+				Origin::signed(target.clone()),
+				origin,
+				LinkMode::Append,
+				EXPIRING_BLOCK_NUMBER
+			),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn test_unlink_without_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+		setup_deposits(&origin);
+
+		// A link made before deposits were introduced, next to funds reserved by another pallet
+		let link = LinkRecord {
+			address: target.clone(),
+			linked_at: 0,
+			verified_at: 0,
+			proof: LinkProof::Unknown,
+			label: None,
+		};
+		PolkadotLink::<Test>::insert(&origin, BoundedVec::try_from(vec![link]).unwrap());
+		assert_ok!(Balances::reserve(&origin, 50));
+
+		assert_ok!(AccountLinker::unlink_polkadot(Origin::signed(origin.clone()), target));
+		assert_eq!(Balances::reserved_balance(&origin), 50);
+		assert!(!Deposits::<Test>::contains_key(&origin));
+	});
+}
@@ -1,4 +1,4 @@
-use crate::mock::*;
+use crate::{mock::*, weights::WeightInfo, LinkExclusivity, LinkMode};
 
 use codec::Encode;
 use frame_support::{assert_noop, assert_ok};
@@ -8,13 +8,55 @@ use parity_crypto::{
 };
 use sp_runtime::AccountId32;
 
-fn generate_msg(account: &AccountId32, block_number: u32) -> Message {
-	let mut bytes = b"\x19Ethereum Signed Message:\n51Link Litentry: ".encode();
+fn generate_msg(account: &AccountId32, block_number: u32, nonce: u32) -> Message {
+	generate_domain_msg(&link_domain(), account, block_number, nonce)
+}
+
+// The link message of a chain with a 33 bytes domain tag
+fn generate_domain_msg(
+	domain: &[u8],
+	account: &AccountId32,
+	block_number: u32,
+	nonce: u32,
+) -> Message {
+	let mut bytes = b"\x19Ethereum Signed Message:\n88Link Litentry: ".encode();
+	bytes.extend_from_slice(domain);
 	let mut account_vec = account.encode();
 	let mut expiring_block_number_vec = block_number.encode();
+	let mut nonce_vec = nonce.encode();
 
 	bytes.append(&mut account_vec);
 	bytes.append(&mut expiring_block_number_vec);
+	bytes.append(&mut nonce_vec);
+
+	Message::from(bytes.keccak256())
+}
+
+fn uint256(value: u128) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[16..].copy_from_slice(&value.to_be_bytes());
+	word
+}
+
+fn generate_eip712_msg(account: &AccountId32, block_number: u32, nonce: u32) -> Message {
+	let mut domain = b"EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)"
+		.keccak256()
+		.to_vec();
+	domain.extend_from_slice(&b"AccountLinker".keccak256());
+	domain.extend_from_slice(&b"1".keccak256());
+	domain.extend_from_slice(&uint256(EthChainId::get() as u128));
+	domain.extend_from_slice(System::block_hash(0).as_ref());
+
+	let mut link = b"Link(bytes account,uint256 expiringBlockNumber,uint256 nonce)"
+		.keccak256()
+		.to_vec();
+	link.extend_from_slice(&account.encode().keccak256());
+	link.extend_from_slice(&uint256(block_number as u128));
+	link.extend_from_slice(&uint256(nonce as u128));
+
+	let mut bytes = b"\x19\x01".to_vec();
+	bytes.extend_from_slice(&domain.keccak256());
+	bytes.extend_from_slice(&link.keccak256());
 
 	Message::from(bytes.keccak256())
 }
@@ -32,15 +74,16 @@ fn test_expired_block_number_eth() {
 		let mut gen = Random {};
 		let key_pair = gen.generate();
 
-		let msg = generate_msg(&account, block_number);
+		let msg = generate_msg(&account, block_number, 0);
 		let sig = generate_sig(&key_pair, &msg);
 
 		assert_noop!(
 			AccountLinker::link_eth(
 				Origin::signed(account.clone()),
-				0,
+				LinkMode::Append,
 				key_pair.address().to_fixed_bytes(),
 				block_number,
+				0,
 				sig
 			),
 			AccountLinkerError::LinkRequestExpired
@@ -52,20 +95,21 @@ fn test_expired_block_number_eth() {
 fn test_invalid_expiring_block_number_eth() {
 	new_test_ext().execute_with(|| {
 		let account: AccountId32 = AccountId32::from([0u8; 32]);
-		let block_number: u32 = crate::EXPIRING_BLOCK_NUMBER_MAX + 1;
+		let block_number: u32 = ExpiringBlockNumberMax::get() + 1;
 
 		let mut gen = Random {};
 		let key_pair = gen.generate();
 
-		let msg = generate_msg(&account, block_number);
+		let msg = generate_msg(&account, block_number, 0);
 		let sig = generate_sig(&key_pair, &msg);
 
 		assert_noop!(
 			AccountLinker::link_eth(
 				Origin::signed(account.clone()),
-				0,
+				LinkMode::Append,
 				key_pair.address().to_fixed_bytes(),
 				block_number,
+				0,
 				sig
 			),
 			AccountLinkerError::InvalidExpiringBlockNumber
@@ -82,15 +126,16 @@ fn test_unexpected_address_eth() {
 		let mut gen = Random {};
 		let key_pair = gen.generate();
 
-		let msg = generate_msg(&account, block_number);
+		let msg = generate_msg(&account, block_number, 0);
 		let sig = generate_sig(&key_pair, &msg);
 
 		assert_noop!(
 			AccountLinker::link_eth(
 				Origin::signed(account.clone()),
-				0,
+				LinkMode::Append,
 				gen.generate().address().to_fixed_bytes(),
 				block_number,
+				0,
 				sig
 			),
 			AccountLinkerError::UnexpectedAddress
@@ -112,14 +157,15 @@ fn test_insert_eth_address() {
 		for i in 0..(MAX_ETH_LINKS) {
 			let key_pair = gen.generate();
 
-			let msg = generate_msg(&account, block_number + i as u32);
+			let msg = generate_msg(&account, block_number + i as u32, i as u32);
 			let sig = generate_sig(&key_pair, &msg);
 
 			assert_ok!(AccountLinker::link_eth(
 				Origin::signed(account.clone()),
-				i as u32,
+				LinkMode::Append,
 				key_pair.address().to_fixed_bytes(),
 				block_number + i as u32,
+				i as u32,
 				sig
 			));
 
@@ -146,14 +192,15 @@ fn test_update_eth_address() {
 		let mut gen = Random {};
 		for i in 0..(MAX_ETH_LINKS) {
 			let key_pair = gen.generate();
-			let msg = generate_msg(&account, block_number + i as u32);
+			let msg = generate_msg(&account, block_number + i as u32, i as u32);
 			let sig = generate_sig(&key_pair, &msg);
 
 			assert_ok!(AccountLinker::link_eth(
 				Origin::signed(account.clone()),
-				i as u32,
+				LinkMode::Append,
 				key_pair.address().to_fixed_bytes(),
 				block_number + i as u32,
+				i as u32,
 				sig
 			));
 		}
@@ -164,14 +211,15 @@ fn test_update_eth_address() {
 		// Update addr at slot `index`
 		let key_pair = gen.generate();
 		let block_number = block_number + 9 as u32;
-		let msg = generate_msg(&account, block_number);
+		let msg = generate_msg(&account, block_number, MAX_ETH_LINKS as u32);
 		let sig = generate_sig(&key_pair, &msg);
 
 		assert_ok!(AccountLinker::link_eth(
 			Origin::signed(account.clone()),
-			index,
+			LinkMode::Replace(index),
 			key_pair.address().to_fixed_bytes(),
 			block_number,
+			MAX_ETH_LINKS as u32,
 			sig
 		));
 
@@ -190,27 +238,620 @@ fn test_eth_address_pool_overflow() {
 		let mut gen = Random {};
 		let mut expected_vec = Vec::new();
 
-		for index in 0..(MAX_ETH_LINKS * 2) {
+		for nonce in 0..(MAX_ETH_LINKS as u32) {
 			let key_pair = gen.generate();
 
-			let msg = generate_msg(&account, block_number);
+			let msg = generate_msg(&account, block_number, nonce);
 			let sig = generate_sig(&key_pair, &msg);
 
 			assert_ok!(AccountLinker::link_eth(
 				Origin::signed(account.clone()),
-				index as u32,
+				LinkMode::Append,
 				key_pair.address().to_fixed_bytes(),
 				block_number,
+				nonce,
 				sig
 			));
-
-			if index < MAX_ETH_LINKS {
-				expected_vec.push(key_pair.address().to_fixed_bytes());
-			} else {
-				expected_vec[MAX_ETH_LINKS - 1] = key_pair.address().to_fixed_bytes();
-			}
+			expected_vec.push(key_pair.address().to_fixed_bytes());
 		}
-		assert_eq!(AccountLinker::eth_addresses(&account).len(), MAX_ETH_LINKS);
+
+		// Appending to a full pool must not evict any linked address
+		let key_pair = gen.generate();
+		let msg = generate_msg(&account, block_number, MAX_ETH_LINKS as u32);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				MAX_ETH_LINKS as u32,
+				sig
+			),
+			AccountLinkerError::LinkSlotsFull
+		);
 		assert_eq!(AccountLinker::eth_addresses(&account), expected_vec);
 	});
 }
+
+#[test]
+fn test_replace_eth_address_out_of_range() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([114u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+
+		let msg = generate_msg(&account, block_number, 0);
+		let sig = generate_sig(&key_pair, &msg);
+
+		// There is no linked address to replace yet
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Replace(0),
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::InvalidLinkIndex
+		);
+
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			key_pair.address().to_fixed_bytes(),
+			block_number,
+			0,
+			sig
+		));
+
+		let key_pair = gen.generate();
+		let msg = generate_msg(&account, block_number, 1);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Replace(1),
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				1,
+				sig
+			),
+			AccountLinkerError::InvalidLinkIndex
+		);
+	});
+}
+
+#[test]
+fn test_eth_address_already_linked() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([115u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+		let other = gen.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		for (nonce, key_pair) in [&key_pair, &other].iter().enumerate() {
+			let msg = generate_msg(&account, block_number, nonce as u32);
+			assert_ok!(AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				nonce as u32,
+				generate_sig(key_pair, &msg)
+			));
+		}
+
+		// The address can't take a second slot
+		let msg = generate_msg(&account, block_number, 2);
+		let sig = generate_sig(&key_pair, &msg);
+		for mode in [LinkMode::Append, LinkMode::Replace(1)].iter() {
+			assert_noop!(
+				AccountLinker::link_eth(
+					Origin::signed(account.clone()),
+					*mode,
+					addr,
+					block_number,
+					2,
+					sig
+				),
+				AccountLinkerError::AlreadyLinked
+			);
+		}
+
+		// but it can be linked again at its own slot
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(account.clone()),
+			LinkMode::Replace(0),
+			addr,
+			block_number,
+			2,
+			sig
+		));
+		assert_eq!(
+			AccountLinker::eth_addresses(&account),
+			vec![addr, other.address().to_fixed_bytes()]
+		);
+	});
+}
+
+#[test]
+fn test_eth_linked_accounts() {
+	new_test_ext().execute_with(|| {
+		let alice: AccountId32 = AccountId32::from([20u8; 32]);
+		let bob: AccountId32 = AccountId32::from([21u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let shared = gen.generate();
+		let replacement = gen.generate();
+
+		for account in &[alice.clone(), bob.clone()] {
+			let msg = generate_msg(account, block_number, 0);
+			assert_ok!(AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				shared.address().to_fixed_bytes(),
+				block_number,
+				0,
+				generate_sig(&shared, &msg)
+			));
+		}
+
+		let mut linked = AccountLinker::eth_linked_accounts(&shared.address().to_fixed_bytes());
+		linked.sort();
+		let mut expected = vec![alice.clone(), bob.clone()];
+		expected.sort();
+		assert_eq!(linked, expected);
+
+		// Replacing the address drops it from the reverse index of that account only
+		let msg = generate_msg(&alice, block_number, 1);
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(alice.clone()),
+			LinkMode::Replace(0),
+			replacement.address().to_fixed_bytes(),
+			block_number,
+			1,
+			generate_sig(&replacement, &msg)
+		));
+		assert_eq!(
+			AccountLinker::eth_linked_accounts(&shared.address().to_fixed_bytes()),
+			vec![bob.clone()]
+		);
+		assert_eq!(
+			AccountLinker::eth_linked_accounts(&replacement.address().to_fixed_bytes()),
+			vec![alice.clone()]
+		);
+
+		assert_ok!(AccountLinker::unlink_eth(
+			Origin::signed(bob.clone()),
+			shared.address().to_fixed_bytes()
+		));
+		assert!(AccountLinker::eth_linked_accounts(&shared.address().to_fixed_bytes()).is_empty());
+		assert!(AccountLinker::is_eth_linked(&replacement.address().to_fixed_bytes(), &alice));
+		assert!(!AccountLinker::is_eth_linked(&shared.address().to_fixed_bytes(), &bob));
+	});
+}
+
+#[test]
+fn test_eth_link_exclusivity_reject() {
+	new_test_ext().execute_with(|| {
+		Exclusivity::set(&LinkExclusivity::Reject);
+
+		let alice: AccountId32 = AccountId32::from([22u8; 32]);
+		let bob: AccountId32 = AccountId32::from([23u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		let msg = generate_msg(&alice, block_number, 0);
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(alice.clone()),
+			LinkMode::Append,
+			addr,
+			block_number,
+			0,
+			generate_sig(&key_pair, &msg)
+		));
+
+		let msg = generate_msg(&bob, block_number, 0);
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(bob.clone()),
+				LinkMode::Append,
+				addr,
+				block_number,
+				0,
+				generate_sig(&key_pair, &msg)
+			),
+			AccountLinkerError::AddressAlreadyLinked
+		);
+
+		// The account holding the address can still relink it
+		let msg = generate_msg(&alice, block_number, 1);
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(alice.clone()),
+			LinkMode::Replace(0),
+			addr,
+			block_number,
+			1,
+			generate_sig(&key_pair, &msg)
+		));
+		assert_eq!(AccountLinker::eth_linked_accounts(&addr), vec![alice]);
+	});
+}
+
+#[test]
+fn test_eth_link_exclusivity_transfer() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		Exclusivity::set(&LinkExclusivity::Transfer);
+
+		let alice: AccountId32 = AccountId32::from([24u8; 32]);
+		let bob: AccountId32 = AccountId32::from([25u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		let msg = generate_msg(&alice, block_number, 0);
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(alice.clone()),
+			LinkMode::Append,
+			addr,
+			block_number,
+			0,
+			generate_sig(&key_pair, &msg)
+		));
+		events();
+
+		let msg = generate_msg(&bob, block_number, 0);
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(bob.clone()),
+			LinkMode::Append,
+			addr,
+			block_number,
+			0,
+			generate_sig(&key_pair, &msg)
+		));
+
+		assert_eq!(
+			events(),
+			[
+				Event::AccountLinker(crate::Event::EthAddressTransferred(
+					alice.clone(),
+					bob.clone(),
+					addr.to_vec()
+				)),
+				Event::AccountLinker(crate::Event::EthAddressLinked(bob.clone(), addr.to_vec())),
+			]
+		);
+		assert!(AccountLinker::eth_addresses(&alice).is_empty());
+		assert!(!crate::EthereumLink::<Test>::contains_key(&alice));
+		assert_eq!(AccountLinker::eth_addresses(&bob), vec![addr]);
+		assert_eq!(AccountLinker::eth_linked_accounts(&addr), vec![bob]);
+	});
+}
+
+#[test]
+fn test_eth_link_transfer_bounded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		// linked to more accounts than a link call transfers it from while it was shared
+		let holders: Vec<AccountId32> = (0..MaxDisplacedAccounts::get() as u8 + 1)
+			.map(|i| AccountId32::from([30 + i; 32]))
+			.collect();
+		for holder in holders.iter() {
+			link_eth(holder, &key_pair, LinkMode::Append);
+		}
+		Exclusivity::set(&LinkExclusivity::Transfer);
+
+		let dave: AccountId32 = AccountId32::from([40u8; 32]);
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(dave.clone()),
+				LinkMode::Append,
+				addr,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				eth_link_sig(&dave, &key_pair, 0),
+			),
+			AccountLinkerError::TooManyDisplacedAccounts
+		);
+
+		// once one of them unlinks it, it's transferred from the others
+		assert_ok!(AccountLinker::unlink_eth(Origin::signed(holders[0].clone()), addr));
+		let info = AccountLinker::link_eth(
+			Origin::signed(dave.clone()),
+			LinkMode::Append,
+			addr,
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			eth_link_sig(&dave, &key_pair, 0),
+		)
+		.unwrap();
+		assert_eq!(
+			info.actual_weight,
+			Some(<() as WeightInfo>::link_eth(MaxDisplacedAccounts::get()))
+		);
+		assert_eq!(AccountLinker::eth_linked_accounts(&addr), vec![dave]);
+	});
+}
+
+#[test]
+fn test_unlink_eth_address() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([7u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let mut linked = Vec::new();
+
+		for i in 0..(MAX_ETH_LINKS) {
+			let key_pair = gen.generate();
+			let msg = generate_msg(&account, block_number + i as u32, i as u32);
+			let sig = generate_sig(&key_pair, &msg);
+
+			assert_ok!(AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				block_number + i as u32,
+				i as u32,
+				sig
+			));
+			linked.push(key_pair.address().to_fixed_bytes());
+		}
+		events();
+
+		let removed = linked.remove(1);
+		assert_ok!(AccountLinker::unlink_eth(Origin::signed(account.clone()), removed));
+		assert_eq!(AccountLinker::eth_addresses(&account), linked);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthAddressUnlinked(
+				account.clone(),
+				removed.to_vec()
+			)),]
+		);
+
+		for addr in linked {
+			assert_ok!(AccountLinker::unlink_eth(Origin::signed(account.clone()), addr));
+		}
+		assert!(!crate::EthereumLink::<Test>::contains_key(&account));
+	});
+}
+
+#[test]
+fn test_unlink_eth_address_not_linked() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([8u8; 32]);
+
+		let mut gen = Random {};
+
+		assert_noop!(
+			AccountLinker::unlink_eth(
+				Origin::signed(account.clone()),
+				gen.generate().address().to_fixed_bytes()
+			),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
+
+#[test]
+fn test_insert_eth_address_eip712() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([9u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+
+		let msg = generate_eip712_msg(&account, block_number, 0);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_ok!(AccountLinker::link_eth_eip712(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			key_pair.address().to_fixed_bytes(),
+			block_number,
+			0,
+			sig
+		));
+
+		assert_eq!(
+			AccountLinker::eth_addresses(&account),
+			vec![key_pair.address().to_fixed_bytes()]
+		);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthAddressLinked(
+				account.clone(),
+				key_pair.address().to_fixed_bytes().to_vec()
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_eip712_domain() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([9u8; 32]);
+
+		let domain = AccountLinker::eip712_domain();
+		assert_eq!(domain.name, b"AccountLinker".to_vec());
+		assert_eq!(domain.version, b"1".to_vec());
+		assert_eq!(domain.chain_id, EthChainId::get());
+		assert_eq!(&domain.salt[..], System::block_hash(0).as_ref());
+
+		// the hash the wallet signs for the domain and the `Link` message
+		assert_eq!(
+			Message::from(AccountLinker::eip712_link_hash(&account, 99999, 1)),
+			generate_eip712_msg(&account, 99999, 1)
+		);
+	});
+}
+
+#[test]
+fn test_personal_sign_rejected_by_eip712() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([10u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+
+		let msg = generate_msg(&account, block_number, 0);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_noop!(
+			AccountLinker::link_eth_eip712(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+	});
+}
+
+#[test]
+fn test_eth_link_signature_replay() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([11u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		let msg = generate_msg(&account, block_number, 0);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_eq!(AccountLinker::link_nonce(&account), 0);
+		assert_ok!(AccountLinker::link_eth(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr,
+			block_number,
+			0,
+			sig
+		));
+		assert_eq!(AccountLinker::link_nonce(&account), 1);
+
+		// The same signature cannot be submitted again, even after unlinking the address
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				addr,
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::StaleLinkNonce
+		);
+		assert_ok!(AccountLinker::unlink_eth(Origin::signed(account.clone()), addr));
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				addr,
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::StaleLinkNonce
+		);
+
+		// Nor can it be passed off as a signature over the next nonce
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				addr,
+				block_number,
+				1,
+				sig
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+	});
+}
+
+#[test]
+fn test_eth_link_other_chain() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([13u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+
+		// A signature collected on a chain with another genesis hash
+		let mut domain = vec![42u8; 32];
+		domain.push(2);
+		let msg = generate_domain_msg(&domain, &account, block_number, 0);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				0,
+				sig
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+	});
+}
+
+#[test]
+fn test_eth_link_future_nonce() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([12u8; 32]);
+		let block_number: u32 = 99999;
+
+		let mut gen = Random {};
+		let key_pair = gen.generate();
+
+		let msg = generate_msg(&account, block_number, 1);
+		let sig = generate_sig(&key_pair, &msg);
+
+		assert_noop!(
+			AccountLinker::link_eth(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				block_number,
+				1,
+				sig
+			),
+			AccountLinkerError::StaleLinkNonce
+		);
+	});
+}
@@ -0,0 +1,313 @@
+use crate::{mock::*, LinkExpiry, LinkMode, LinkProof, LinkedAccountsProvider, LinkedAddress};
+
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok};
+use parity_crypto::publickey::{sign, Generator, KeyPair, Message, Random};
+use sp_core::{ed25519, sr25519, Pair};
+use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSignature, MultiSigner};
+
+fn text_message(account: &AccountId32, nonce: u32) -> Vec<u8> {
+	format!("Link Litentry: {}", hex::encode(link_payload(account, nonce))).into_bytes()
+}
+
+fn refresh_eth(account: &AccountId32, key_pair: &KeyPair) {
+	let nonce = AccountLinker::link_nonce(account);
+	assert_ok!(AccountLinker::refresh_link(
+		Origin::signed(account.clone()),
+		LinkedAddress::Ethereum(key_pair.address().to_fixed_bytes()),
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		eth_link_sig(account, key_pair, nonce).to_vec()
+	));
+}
+
+#[test]
+fn test_links_never_stale_without_period() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&account, &key_pair, LinkMode::Append);
+
+		run_to_block(1000);
+		assert!(!AccountLinker::is_link_stale(&account, &LinkedAddress::Ethereum(addr)));
+		assert_eq!(AccountLinker::fresh_eth_addresses(&account), vec![addr]);
+		assert_eq!(LinkExpiry::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn test_link_goes_stale() {
+	new_test_ext().execute_with(|| {
+		LinkValidityPeriod::set(Some(10));
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&account, &key_pair, LinkMode::Append);
+		events();
+
+		run_to_block(10);
+		assert!(!AccountLinker::is_link_stale(&account, &LinkedAddress::Ethereum(addr)));
+		assert_eq!(events(), []);
+
+		run_to_block(11);
+		assert!(AccountLinker::is_link_stale(&account, &LinkedAddress::Ethereum(addr)));
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::LinkStale(
+				account.clone(),
+				LinkedAddress::Ethereum(addr)
+			))]
+		);
+
+		// the stale link is kept, only its consumers skip it
+		assert_eq!(AccountLinker::eth_addresses(&account), vec![addr]);
+		assert_eq!(AccountLinker::fresh_eth_addresses(&account), vec![]);
+		assert!(AccountLinker::linked_identity(&account).ethereum[0].stale);
+		assert_eq!(LinkExpiry::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn test_refresh_link() {
+	new_test_ext().execute_with(|| {
+		LinkValidityPeriod::set(Some(10));
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&account, &key_pair, LinkMode::Append);
+
+		run_to_block(8);
+		events();
+		refresh_eth(&account, &key_pair);
+
+		let link = &AccountLinker::eth_links(&account)[0];
+		assert_eq!((link.linked_at, link.verified_at), (1, 8));
+		assert_eq!(link.proof, LinkProof::PersonalSign);
+		assert_eq!(AccountLinker::link_nonce(&account), 2);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::LinkRefreshed(
+				account.clone(),
+				LinkedAddress::Ethereum(addr)
+			))]
+		);
+
+		// the entry of the first link is moved to the block the refreshed one goes stale at
+		run_to_block(17);
+		assert_eq!(events(), []);
+		assert_eq!(AccountLinker::fresh_eth_addresses(&account), vec![addr]);
+
+		run_to_block(18);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::LinkStale(
+				account.clone(),
+				LinkedAddress::Ethereum(addr)
+			))]
+		);
+
+		// a stale link can be refreshed too
+		refresh_eth(&account, &key_pair);
+		assert!(!AccountLinker::is_link_stale(&account, &LinkedAddress::Ethereum(addr)));
+	});
+}
+
+#[test]
+fn test_refresh_link_keeps_proof() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let hash = AccountLinker::eip712_link_hash(&account, EXPIRING_BLOCK_NUMBER, 0);
+		assert_ok!(AccountLinker::link_eth_eip712(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			key_pair.address().to_fixed_bytes(),
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			sign(key_pair.secret(), &Message::from(hash)).unwrap().into_electrum()
+		));
+
+		// the link is refreshed with a personal_sign signature
+		run_to_block(5);
+		refresh_eth(&account, &key_pair);
+
+		let link = &AccountLinker::eth_links(&account)[0];
+		assert_eq!(link.verified_at, 5);
+		assert_eq!(link.proof, LinkProof::Eip712);
+	});
+}
+
+#[test]
+fn test_refresh_link_other_networks() {
+	new_test_ext().execute_with(|| {
+		LinkValidityPeriod::set(Some(10));
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let solana = ed25519::Pair::from_seed(&[1u8; 32]);
+		assert_ok!(AccountLinker::link_solana(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			solana.public().0,
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			solana.sign(&text_message(&account, 0)).0
+		));
+
+		let pair = sr25519::Pair::from_seed(&[2u8; 32]);
+		let target = MultiSigner::from(pair.public()).into_account();
+		let mut msg = b"Link Litentry: ".to_vec();
+		msg.append(&mut link_payload(&account, 1));
+		assert_ok!(AccountLinker::link_polkadot_signed(
+			Origin::signed(account.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER,
+			1,
+			MultiSignature::from(pair.sign(&msg))
+		));
+
+		run_to_block(5);
+		assert_ok!(AccountLinker::refresh_link(
+			Origin::signed(account.clone()),
+			LinkedAddress::Solana(solana.public().0),
+			EXPIRING_BLOCK_NUMBER,
+			2,
+			solana.sign(&text_message(&account, 2)).0.to_vec()
+		));
+		let mut msg = b"Link Litentry: ".to_vec();
+		msg.append(&mut link_payload(&account, 3));
+		assert_ok!(AccountLinker::refresh_link(
+			Origin::signed(account.clone()),
+			LinkedAddress::Polkadot(target.clone()),
+			EXPIRING_BLOCK_NUMBER,
+			3,
+			MultiSignature::from(pair.sign(&msg)).encode()
+		));
+
+		assert_eq!(AccountLinker::solana_links(&account)[0].verified_at, 5);
+		assert_eq!(AccountLinker::polkadot_links(&account)[0].verified_at, 5);
+
+		run_to_block(11);
+		assert!(!AccountLinker::is_link_stale(&account, &LinkedAddress::Polkadot(target)));
+	});
+}
+
+#[test]
+fn test_refresh_link_fails() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&account, &key_pair, LinkMode::Append);
+
+		let other = Random {}.generate();
+		assert_noop!(
+			AccountLinker::refresh_link(
+				Origin::signed(account.clone()),
+				LinkedAddress::Ethereum(addr),
+				EXPIRING_BLOCK_NUMBER,
+				1,
+				eth_link_sig(&account, &other, 1).to_vec()
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+		assert_noop!(
+			AccountLinker::refresh_link(
+				Origin::signed(account.clone()),
+				LinkedAddress::Ethereum(addr),
+				EXPIRING_BLOCK_NUMBER,
+				1,
+				vec![0u8; 64]
+			),
+			AccountLinkerError::InvalidSignatureLength
+		);
+		assert_noop!(
+			AccountLinker::refresh_link(
+				Origin::signed(account.clone()),
+				LinkedAddress::Ethereum(addr),
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				eth_link_sig(&account, &key_pair, 0).to_vec()
+			),
+			AccountLinkerError::StaleLinkNonce
+		);
+		assert_noop!(
+			AccountLinker::refresh_link(
+				Origin::signed(account.clone()),
+				LinkedAddress::Ethereum(other.address().to_fixed_bytes()),
+				EXPIRING_BLOCK_NUMBER,
+				1,
+				eth_link_sig(&account, &other, 1).to_vec()
+			),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
+
+#[test]
+fn test_unlinked_link_not_announced() {
+	new_test_ext().execute_with(|| {
+		LinkValidityPeriod::set(Some(10));
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&account, &key_pair, LinkMode::Append);
+		assert_ok!(AccountLinker::unlink_eth(Origin::signed(account.clone()), addr));
+		events();
+
+		run_to_block(20);
+		assert_eq!(events(), []);
+		assert!(!AccountLinker::is_link_stale(&account, &LinkedAddress::Ethereum(addr)));
+		assert_eq!(LinkExpiry::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn test_linked_accounts_provider() {
+	new_test_ext().execute_with(|| {
+		LinkValidityPeriod::set(Some(10));
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&account, &key_pair, LinkMode::Append);
+
+		assert_eq!(
+			<AccountLinker as LinkedAccountsProvider<_>>::eth_addresses(&account),
+			vec![addr]
+		);
+		assert_eq!(
+			<AccountLinker as LinkedAccountsProvider<_>>::fresh_eth_addresses(&account),
+			vec![addr]
+		);
+		assert!(<AccountLinker as LinkedAccountsProvider<_>>::btc_addresses(&account).is_empty());
+
+		run_to_block(11);
+		assert_eq!(
+			<AccountLinker as LinkedAccountsProvider<_>>::eth_addresses(&account),
+			vec![addr]
+		);
+		assert!(
+			<AccountLinker as LinkedAccountsProvider<_>>::fresh_eth_addresses(&account).is_empty()
+		);
+		assert!(<AccountLinker as LinkedAccountsProvider<_>>::is_link_stale(
+			&account,
+			&LinkedAddress::Ethereum(addr)
+		));
+	});
+}
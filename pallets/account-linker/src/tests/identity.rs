@@ -0,0 +1,213 @@
+use crate::{
+	mock::*, IdentityLink, LinkMode, LinkProof, LinkRecord, LinkedAddress, LinkedIdentity,
+	PendingPolkadotLink, PolkadotIdentityLink, PolkadotLinkRequest,
+};
+
+use bitcoin::util::base58;
+use frame_support::{assert_noop, assert_ok};
+use parity_crypto::publickey::{Generator, Random};
+use sp_core::{ed25519, sr25519, Pair};
+use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSignature, MultiSigner};
+
+fn link_solana(account: &AccountId32, pair: &ed25519::Pair) {
+	let nonce = AccountLinker::link_nonce(account);
+	let msg = format!("Link Litentry: {}", hex::encode(link_payload(account, nonce)));
+
+	assert_ok!(AccountLinker::link_solana(
+		Origin::signed(account.clone()),
+		LinkMode::Append,
+		pair.public().0,
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		pair.sign(msg.as_bytes()).0
+	));
+}
+
+fn link_polkadot_signed(account: &AccountId32, pair: &sr25519::Pair) -> AccountId32 {
+	let nonce = AccountLinker::link_nonce(account);
+	let mut msg = b"Link Litentry: ".to_vec();
+	msg.append(&mut link_payload(account, nonce));
+	let target = MultiSigner::from(pair.public()).into_account();
+
+	assert_ok!(AccountLinker::link_polkadot_signed(
+		Origin::signed(account.clone()),
+		target.clone(),
+		LinkMode::Append,
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		MultiSignature::from(pair.sign(&msg))
+	));
+	target
+}
+
+#[test]
+fn test_linked_identity() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		link_eth(&account, &key_pair, LinkMode::Append);
+
+		run_to_block(3);
+		let solana = ed25519::Pair::from_seed(&[1u8; 32]);
+		link_solana(&account, &solana);
+
+		run_to_block(5);
+		let target = link_polkadot_signed(&account, &sr25519::Pair::from_seed(&[2u8; 32]));
+		let pending: AccountId32 = AccountId32::from([3u8; 32]);
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(account.clone()),
+			pending.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER
+		));
+
+		assert_eq!(
+			AccountLinker::linked_identity(&account),
+			LinkedIdentity {
+				ethereum: vec![IdentityLink {
+					address: format!("0x{}", hex::encode(key_pair.address())).into_bytes(),
+					linked_at: 1,
+					verified_at: 1,
+					stale: false,
+					proof: LinkProof::PersonalSign,
+					label: None,
+				}],
+				bitcoin: vec![],
+				tron: vec![],
+				solana: vec![IdentityLink {
+					address: base58::encode_slice(&solana.public().0).into_bytes(),
+					linked_at: 3,
+					verified_at: 3,
+					stale: false,
+					proof: LinkProof::Ed25519,
+					label: None,
+				}],
+				cosmos: vec![],
+				polkadot: vec![PolkadotIdentityLink {
+					account: target,
+					linked_at: 5,
+					verified_at: 5,
+					stale: false,
+					proof: LinkProof::MultiSignature,
+					label: None,
+				}],
+				pending_polkadot: vec![PendingPolkadotLink {
+					target: pending,
+					request: PolkadotLinkRequest {
+						mode: LinkMode::Append,
+						expiring_block_number: EXPIRING_BLOCK_NUMBER,
+					},
+				}],
+			}
+		);
+	});
+}
+
+#[test]
+fn test_link_record_replace() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let first = Random {}.generate();
+		let second = Random {}.generate();
+		let first_addr = first.address().to_fixed_bytes();
+		let second_addr = second.address().to_fixed_bytes();
+
+		link_eth(&account, &first, LinkMode::Append);
+		assert_ok!(AccountLinker::set_link_label(
+			Origin::signed(account.clone()),
+			LinkedAddress::Ethereum(first_addr),
+			Some(b"ledger".to_vec())
+		));
+
+		run_to_block(2);
+		link_eth(&account, &second, LinkMode::Replace(0));
+		assert_eq!(
+			AccountLinker::eth_links(&account).to_vec(),
+			vec![LinkRecord {
+				address: second_addr,
+				linked_at: 2,
+				verified_at: 2,
+				proof: LinkProof::PersonalSign,
+				label: None,
+			}]
+		);
+	});
+}
+
+#[test]
+fn test_set_link_label() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let addr = pair.public().0;
+		link_solana(&account, &pair);
+		events();
+
+		assert_ok!(AccountLinker::set_link_label(
+			Origin::signed(account.clone()),
+			LinkedAddress::Solana(addr),
+			Some(b"phantom".to_vec())
+		));
+		assert_eq!(AccountLinker::solana_links(&account)[0].label, Some(b"phantom".to_vec()));
+		assert_eq!(
+			AccountLinker::linked_identity(&account).solana[0].label,
+			Some(b"phantom".to_vec())
+		);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::LinkLabelSet(
+				account.clone(),
+				LinkedAddress::Solana(addr)
+			)),]
+		);
+
+		assert_ok!(AccountLinker::set_link_label(
+			Origin::signed(account.clone()),
+			LinkedAddress::Solana(addr),
+			None
+		));
+		assert_eq!(AccountLinker::solana_links(&account)[0].label, None);
+	});
+}
+
+#[test]
+fn test_set_link_label_fails() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		link_solana(&account, &pair);
+
+		assert_noop!(
+			AccountLinker::set_link_label(
+				Origin::signed(account.clone()),
+				LinkedAddress::Solana(pair.public().0),
+				Some(vec![b'a'; MaxLinkLabelLen::get() as usize + 1])
+			),
+			AccountLinkerError::LinkLabelTooLong
+		);
+		assert_noop!(
+			AccountLinker::set_link_label(
+				Origin::signed(account.clone()),
+				LinkedAddress::Solana([2u8; 32]),
+				Some(b"phantom".to_vec())
+			),
+			AccountLinkerError::LinkNotFound
+		);
+		assert_noop!(
+			AccountLinker::set_link_label(
+				Origin::signed(account),
+				LinkedAddress::Ethereum([2u8; 20]),
+				None
+			),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
@@ -0,0 +1,112 @@
+use crate::{mock::*, LinkExclusivity, LinkMode, LinkedAddress};
+
+use frame_support::assert_ok;
+use parity_crypto::publickey::{Generator, Random};
+use sp_core::{sr25519, Pair};
+use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSignature, MultiSigner};
+
+#[test]
+fn test_link_and_unlink_notified() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		link_eth(&account, &key_pair, LinkMode::Append);
+		assert_eq!(link_changes(), [(account.clone(), LinkedAddress::Ethereum(addr), true)]);
+
+		// a second slot linked before duplicates were rejected doesn't unlink the address when
+		// it's removed
+		crate::EthereumLink::<Test>::mutate(&account, |links| {
+			let link = links[0].clone();
+			links.try_push(link).unwrap();
+		});
+		events();
+		assert_ok!(AccountLinker::unlink_eth(Origin::signed(account.clone()), addr));
+		assert_eq!(link_changes(), [(account.clone(), LinkedAddress::Ethereum(addr), true)]);
+		assert!(events().is_empty());
+
+		assert_ok!(AccountLinker::unlink_eth(Origin::signed(account.clone()), addr));
+		assert_eq!(link_changes(), [(account.clone(), LinkedAddress::Ethereum(addr), false)]);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::EthAddressUnlinked(account, addr.to_vec()))]
+		);
+	});
+}
+
+#[test]
+fn test_replace_notified() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let old = Random {}.generate();
+		let new = Random {}.generate();
+		link_eth(&account, &old, LinkMode::Append);
+		link_changes();
+
+		link_eth(&account, &new, LinkMode::Replace(0));
+		assert_eq!(
+			link_changes(),
+			[
+				(account.clone(), LinkedAddress::Ethereum(old.address().to_fixed_bytes()), false),
+				(account, LinkedAddress::Ethereum(new.address().to_fixed_bytes()), true),
+			]
+		);
+	});
+}
+
+#[test]
+fn test_transfer_notified() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		Exclusivity::set(&LinkExclusivity::Transfer);
+
+		let alice: AccountId32 = AccountId32::from([1u8; 32]);
+		let bob: AccountId32 = AccountId32::from([2u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+		link_eth(&alice, &key_pair, LinkMode::Append);
+		link_changes();
+
+		link_eth(&bob, &key_pair, LinkMode::Append);
+		assert_eq!(
+			link_changes(),
+			[
+				(alice, LinkedAddress::Ethereum(addr), false),
+				(bob, LinkedAddress::Ethereum(addr), true),
+			]
+		);
+	});
+}
+
+#[test]
+fn test_polkadot_link_notified() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = sr25519::Pair::from_seed(&[2u8; 32]);
+		let target = MultiSigner::from(pair.public()).into_account();
+		let mut msg = b"Link Litentry: ".to_vec();
+		msg.append(&mut link_payload(&account, 0));
+		assert_ok!(AccountLinker::link_polkadot_signed(
+			Origin::signed(account.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			MultiSignature::from(pair.sign(&msg))
+		));
+		assert_eq!(
+			link_changes(),
+			[(account.clone(), LinkedAddress::Polkadot(target.clone()), true)]
+		);
+
+		assert_ok!(AccountLinker::unlink_polkadot(Origin::signed(account.clone()), target.clone()));
+		assert_eq!(link_changes(), [(account, LinkedAddress::Polkadot(target), false)]);
+	});
+}
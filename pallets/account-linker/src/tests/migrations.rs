@@ -0,0 +1,321 @@
+use crate::{
+	migrations::{self, v5},
+	mock::*,
+	BitcoinLink, EthContractPendingSweep, EthereumLink, LinkExpiry, LinkExpirySweep, LinkMode,
+	LinkProof, LinkRecord, LinkedAddress, PolkadotLink, PolkadotLinkRequest, PolkadotPendingExpiry,
+	PolkadotPendingSweep, Releases, StorageVersion,
+};
+
+use codec::Encode;
+use frame_support::{
+	storage::{migration::put_storage_value, unhashed},
+	traits::OnRuntimeUpgrade,
+	Blake2_128Concat, BoundedVec, StorageHasher,
+};
+use parity_crypto::publickey::{Generator, Random};
+use sp_runtime::AccountId32;
+use sp_std::convert::TryFrom;
+
+#[test]
+fn test_genesis_starts_at_latest_release() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V6_0_0);
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let key_pair = Random {}.generate();
+		link_eth(&account, &key_pair, LinkMode::Append);
+		let links = AccountLinker::eth_links(&account);
+
+		<AccountLinker as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		assert_eq!(AccountLinker::eth_links(&account), links);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V6_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_v2_truncates_links() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		StorageVersion::<Test>::put(Releases::V1_0_0);
+
+		// Linked address lists were unbounded `Vec`s before v2
+		let eth_addrs: Vec<[u8; 20]> = (0..5u8).map(|i| [i; 20]).collect();
+		unhashed::put(&EthereumLink::<Test>::hashed_key_for(&account), &eth_addrs);
+		let polkadot_addrs: Vec<AccountId32> = vec![AccountId32::from([2u8; 32])];
+		unhashed::put(&PolkadotLink::<Test>::hashed_key_for(&account), &polkadot_addrs);
+
+		migrations::v2::migrate::<Test>();
+
+		// The lists are bounded but still hold bare addresses until v5
+		assert_eq!(
+			unhashed::get::<Vec<[u8; 20]>>(&EthereumLink::<Test>::hashed_key_for(&account)),
+			Some(eth_addrs[..MAX_ETH_LINKS].to_vec())
+		);
+		assert_eq!(
+			unhashed::get::<Vec<AccountId32>>(&PolkadotLink::<Test>::hashed_key_for(&account)),
+			Some(polkadot_addrs)
+		);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V2_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_v2_runs_once() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		StorageVersion::<Test>::put(Releases::V2_0_0);
+
+		let eth_addrs: Vec<[u8; 20]> = (0..5u8).map(|i| [i; 20]).collect();
+		unhashed::put(&EthereumLink::<Test>::hashed_key_for(&account), &eth_addrs);
+
+		migrations::v2::migrate::<Test>();
+
+		assert_eq!(
+			unhashed::get::<Vec<[u8; 20]>>(&EthereumLink::<Test>::hashed_key_for(&account)),
+			Some(eth_addrs)
+		);
+	});
+}
+
+#[test]
+fn test_migrate_v3_indexes_links() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let other: AccountId32 = AccountId32::from([3u8; 32]);
+		StorageVersion::<Test>::put(Releases::V2_0_0);
+
+		unhashed::put(&EthereumLink::<Test>::hashed_key_for(&account), &vec![[7u8; 20]]);
+		unhashed::put(&EthereumLink::<Test>::hashed_key_for(&other), &vec![[7u8; 20]]);
+		unhashed::put(&BitcoinLink::<Test>::hashed_key_for(&account), &vec![b"1btc".to_vec()]);
+		unhashed::put(&PolkadotLink::<Test>::hashed_key_for(&account), &vec![other.clone()]);
+
+		migrations::v3::migrate::<Test>();
+
+		let mut linked = AccountLinker::eth_linked_accounts(&[7u8; 20]);
+		linked.sort();
+		assert_eq!(linked, vec![account.clone(), other.clone()]);
+		assert_eq!(AccountLinker::btc_linked_accounts(b"1btc"), vec![account.clone()]);
+		assert_eq!(AccountLinker::polkadot_linked_accounts(&other), vec![account]);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V3_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_v4_moves_polkadot_pending() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let target: AccountId32 = AccountId32::from([2u8; 32]);
+		StorageVersion::<Test>::put(Releases::V3_0_0);
+		System::set_block_number(10);
+
+		// There was a single pending request per account before v4, holding the slot index
+		let linked = vec![AccountId32::from([3u8; 32]), AccountId32::from([4u8; 32])];
+		unhashed::put(&PolkadotLink::<Test>::hashed_key_for(&account), &linked);
+		put_storage_value(
+			b"AccountLinker",
+			b"PolkadotPending",
+			&Blake2_128Concat::hash(&account.encode()),
+			(target.clone(), 1u32),
+		);
+
+		migrations::v4::migrate::<Test>();
+
+		let expiring_block_number = 10 + ExpiringBlockNumberMax::get();
+		assert_eq!(
+			AccountLinker::polkadot_pending(&account, &target),
+			Some(PolkadotLinkRequest { mode: LinkMode::Replace(1), expiring_block_number })
+		);
+		assert!(PolkadotPendingExpiry::<Test>::contains_key(
+			expiring_block_number,
+			(&account, &target)
+		));
+		assert_eq!(PolkadotPendingSweep::<Test>::get(), 10);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V4_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_v4_pending_link_mode() {
+	let max = MaxPolkadotLinks::get();
+	assert_eq!(migrations::v4::link_mode(1, 2, max), LinkMode::Replace(1));
+	// an index past the end appended the target
+	assert_eq!(migrations::v4::link_mode(5, 2, max), LinkMode::Append);
+	assert_eq!(migrations::v4::link_mode(0, 0, max), LinkMode::Append);
+	// or replaced the last slot of a full list
+	assert_eq!(migrations::v4::link_mode(5, max, max), LinkMode::Replace(max - 1));
+}
+
+#[test]
+fn test_migrate_v5_records_links() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let target: AccountId32 = AccountId32::from([2u8; 32]);
+		StorageVersion::<Test>::put(Releases::V4_0_0);
+		System::set_block_number(10);
+
+		unhashed::put(&EthereumLink::<Test>::hashed_key_for(&account), &vec![[7u8; 20], [8u8; 20]]);
+		unhashed::put(&PolkadotLink::<Test>::hashed_key_for(&account), &vec![target.clone()]);
+
+		migrations::v5::migrate::<Test>();
+
+		let record = |address, linked_at| v5::LinkRecord {
+			address,
+			linked_at,
+			proof: LinkProof::Unknown,
+			label: None,
+		};
+		assert_eq!(
+			unhashed::get::<Vec<v5::LinkRecord<[u8; 20], u32>>>(
+				&EthereumLink::<Test>::hashed_key_for(&account)
+			),
+			Some(vec![record([7u8; 20], 10), record([8u8; 20], 10)])
+		);
+		assert_eq!(
+			unhashed::get::<Vec<v5::LinkRecord<AccountId32, u32>>>(
+				&PolkadotLink::<Test>::hashed_key_for(&account)
+			),
+			Some(vec![v5::LinkRecord {
+				address: target,
+				linked_at: 10,
+				proof: LinkProof::Unknown,
+				label: None
+			}])
+		);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V5_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_v5_runs_once() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let link = LinkRecord {
+			address: [7u8; 20],
+			linked_at: 3,
+			verified_at: 3,
+			proof: LinkProof::Eip712,
+			label: None,
+		};
+		EthereumLink::<Test>::insert(&account, BoundedVec::try_from(vec![link.clone()]).unwrap());
+		StorageVersion::<Test>::put(Releases::V5_0_0);
+
+		migrations::v5::migrate::<Test>();
+
+		assert_eq!(AccountLinker::eth_links(&account).to_vec(), vec![link]);
+	});
+}
+
+#[test]
+fn test_migrate_v6_records_verification() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		StorageVersion::<Test>::put(Releases::V5_0_0);
+		LinkValidityPeriod::set(Some(20));
+		System::set_block_number(30);
+
+		let record = |address, linked_at| v5::LinkRecord {
+			address,
+			linked_at,
+			proof: LinkProof::PersonalSign,
+			label: Some(b"ledger".to_vec()),
+		};
+		unhashed::put(
+			&EthereumLink::<Test>::hashed_key_for(&account),
+			&vec![record([7u8; 20], 2), record([8u8; 20], 25)],
+		);
+
+		migrations::v6::migrate::<Test>();
+
+		let link = |address, linked_at| LinkRecord {
+			address,
+			linked_at,
+			verified_at: linked_at,
+			proof: LinkProof::PersonalSign,
+			label: Some(b"ledger".to_vec()),
+		};
+		assert_eq!(
+			AccountLinker::eth_links(&account).to_vec(),
+			vec![link([7u8; 20], 2), link([8u8; 20], 25)]
+		);
+		// the link that is already stale is announced by the first sweep
+		let stale = (account.clone(), LinkedAddress::Ethereum([7u8; 20]));
+		assert_eq!(LinkExpiry::<Test>::get(30, stale), Some(()));
+		assert_eq!(
+			LinkExpiry::<Test>::get(45, (account, LinkedAddress::Ethereum([8u8; 20]))),
+			Some(())
+		);
+		assert_eq!(LinkExpirySweep::<Test>::get(), 30);
+		assert_eq!(EthContractPendingSweep::<Test>::get(), 30);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V6_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_v6_runs_once() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let link = LinkRecord {
+			address: [7u8; 20],
+			linked_at: 3,
+			verified_at: 5,
+			proof: LinkProof::Eip712,
+			label: None,
+		};
+		EthereumLink::<Test>::insert(&account, BoundedVec::try_from(vec![link.clone()]).unwrap());
+		StorageVersion::<Test>::put(Releases::V6_0_0);
+
+		migrations::v6::migrate::<Test>();
+
+		assert_eq!(AccountLinker::eth_links(&account).to_vec(), vec![link]);
+	});
+}
+
+#[test]
+fn test_migrate_v1_to_latest() {
+	new_test_ext().execute_with(|| {
+		let account: AccountId32 = AccountId32::from([1u8; 32]);
+		let target: AccountId32 = AccountId32::from([2u8; 32]);
+		// A chain whose genesis predates the storage version
+		StorageVersion::<Test>::kill();
+		System::set_block_number(10);
+
+		let eth_addrs: Vec<[u8; 20]> = (0..5u8).map(|i| [i; 20]).collect();
+		unhashed::put(&EthereumLink::<Test>::hashed_key_for(&account), &eth_addrs);
+		unhashed::put(&PolkadotLink::<Test>::hashed_key_for(&account), &vec![target.clone()]);
+		put_storage_value(
+			b"AccountLinker",
+			b"PolkadotPending",
+			&Blake2_128Concat::hash(&account.encode()),
+			(AccountId32::from([3u8; 32]), 0u32),
+		);
+
+		<AccountLinker as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		let link = |address| LinkRecord {
+			address,
+			linked_at: 10,
+			verified_at: 10,
+			proof: LinkProof::Unknown,
+			label: None,
+		};
+		let eth_links: Vec<_> = eth_addrs[..MAX_ETH_LINKS].iter().cloned().map(link).collect();
+		assert_eq!(AccountLinker::eth_links(&account).to_vec(), eth_links);
+		assert_eq!(AccountLinker::eth_linked_accounts(&[0u8; 20]), vec![account.clone()]);
+		assert_eq!(AccountLinker::polkadot_links(&account).to_vec(), vec![link(target.clone())]);
+		assert_eq!(AccountLinker::polkadot_linked_accounts(&target), vec![account.clone()]);
+		assert_eq!(
+			AccountLinker::polkadot_pending(&account, &AccountId32::from([3u8; 32])),
+			Some(PolkadotLinkRequest {
+				mode: LinkMode::Replace(0),
+				expiring_block_number: 10 + ExpiringBlockNumberMax::get()
+			})
+		);
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V6_0_0);
+
+		// and the migrated links are left as they are by the next upgrade
+		<AccountLinker as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(AccountLinker::eth_links(&account).to_vec(), eth_links);
+	});
+}
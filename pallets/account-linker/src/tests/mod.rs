@@ -1,3 +1,12 @@
 mod btc;
+mod contract;
+mod cosmos;
+mod deposits;
 mod eth;
+mod freshness;
+mod identity;
+mod link_changes;
+mod migrations;
 mod polkadot;
+mod solana;
+mod tron;
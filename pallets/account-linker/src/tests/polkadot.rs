@@ -1,7 +1,37 @@
-use crate::mock::*;
+use crate::{mock::*, LinkMode, PolkadotLinkRequest};
 
+use codec::Encode;
 use frame_support::{assert_noop, assert_ok};
-use sp_runtime::AccountId32;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSignature, MultiSigner};
+
+const EXPIRING_BLOCK_NUMBER: u32 = 100;
+
+fn generate_msg(account: &AccountId32, block_number: u32, nonce: u32) -> Vec<u8> {
+	let mut bytes = b"Link Litentry: ".to_vec();
+	bytes.append(&mut link_domain());
+	bytes.append(&mut account.encode());
+	bytes.append(&mut block_number.encode());
+	bytes.append(&mut nonce.encode());
+	bytes
+}
+
+fn link_signed<P: Pair>(origin: &AccountId32, pair: &P, msg: &[u8]) -> AccountId32
+where
+	MultiSigner: From<P::Public>,
+	MultiSignature: From<P::Signature>,
+{
+	let target = MultiSigner::from(pair.public()).into_account();
+	assert_ok!(AccountLinker::link_polkadot_signed(
+		Origin::signed(origin.clone()),
+		target.clone(),
+		LinkMode::Append,
+		EXPIRING_BLOCK_NUMBER,
+		AccountLinker::link_nonce(origin),
+		MultiSignature::from(pair.sign(msg))
+	));
+	target
+}
 
 #[test]
 fn test_insert_polkadot_address() {
@@ -11,11 +41,23 @@ fn test_insert_polkadot_address() {
 		let origin: AccountId32 = AccountId32::from([0u8; 32]);
 		let target: AccountId32 = AccountId32::from([1u8; 32]);
 
-		assert_ok!(AccountLinker::link_polkadot(Origin::signed(origin.clone()), target.clone(), 0));
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER
+		));
 
-		assert_eq!(AccountLinker::polkadot_pending(&origin), (target.clone(), 0));
+		assert_eq!(
+			AccountLinker::polkadot_pending(&origin, &target),
+			Some(PolkadotLinkRequest {
+				mode: LinkMode::Append,
+				expiring_block_number: EXPIRING_BLOCK_NUMBER
+			})
+		);
 
 		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(target.clone()), origin.clone()));
+		assert_eq!(AccountLinker::polkadot_pending(&origin, &target), None);
 
 		assert_eq!(
 			events(),
@@ -25,7 +67,9 @@ fn test_insert_polkadot_address() {
 			)),]
 		);
 
-		assert_eq!(AccountLinker::polkadot_addresses(&origin), vec![target]);
+		assert_eq!(AccountLinker::polkadot_addresses(&origin), vec![target.clone()]);
+		assert_eq!(AccountLinker::polkadot_linked_accounts(&target), vec![origin.clone()]);
+		assert!(AccountLinker::is_polkadot_linked(&target, &origin));
 	});
 }
 
@@ -37,13 +81,423 @@ fn test_no_polkadot_pending_address() {
 		let origin: AccountId32 = AccountId32::from([0u8; 32]);
 		let target: AccountId32 = AccountId32::from([1u8; 32]);
 
+		assert_eq!(AccountLinker::polkadot_pending(&origin, &target), None);
+
+		assert_noop!(
+			AccountLinker::accept_polkadot(Origin::signed(target.clone()), origin.clone()),
+			AccountLinkerError::WrongPendingRequest
+		);
+	});
+}
+
+#[test]
+fn test_unlink_polkadot_address() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER
+		));
+		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(target.clone()), origin.clone()));
+		events();
+
+		assert_ok!(AccountLinker::unlink_polkadot(Origin::signed(origin.clone()), target.clone()));
+		assert!(AccountLinker::polkadot_addresses(&origin).is_empty());
+		assert!(!AccountLinker::is_polkadot_linked(&target, &origin));
 		assert_eq!(
-			AccountLinker::polkadot_pending(&origin),
-			(AccountId32::default(), u32::default())
+			events(),
+			[Event::AccountLinker(crate::Event::PolkadotAddressUnlinked(
+				origin.clone(),
+				target.clone()
+			)),]
 		);
 
+		assert_noop!(
+			AccountLinker::unlink_polkadot(Origin::signed(origin.clone()), target.clone()),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
+
+#[test]
+fn test_link_polkadot_to_self() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let pair = sr25519::Pair::from_seed(&[1u8; 32]);
+		let origin = MultiSigner::from(pair.public()).into_account();
+
+		assert_noop!(
+			AccountLinker::link_polkadot(
+				Origin::signed(origin.clone()),
+				origin.clone(),
+				LinkMode::Append,
+				EXPIRING_BLOCK_NUMBER
+			),
+			AccountLinkerError::LinkToSelf
+		);
+
+		let msg = generate_msg(&origin, EXPIRING_BLOCK_NUMBER, 0);
+		assert_noop!(
+			AccountLinker::link_polkadot_signed(
+				Origin::signed(origin.clone()),
+				origin.clone(),
+				LinkMode::Append,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				MultiSignature::from(pair.sign(&msg))
+			),
+			AccountLinkerError::LinkToSelf
+		);
+	});
+}
+
+#[test]
+fn test_accept_polkadot_slots_full() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+
+		for i in 1..=3u8 {
+			let target: AccountId32 = AccountId32::from([i; 32]);
+			assert_ok!(AccountLinker::link_polkadot(
+				Origin::signed(origin.clone()),
+				target.clone(),
+				LinkMode::Append,
+				EXPIRING_BLOCK_NUMBER
+			));
+			assert_ok!(AccountLinker::accept_polkadot(Origin::signed(target), origin.clone()));
+		}
+
+		let target: AccountId32 = AccountId32::from([4u8; 32]);
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER
+		));
 		assert_noop!(
 			AccountLinker::accept_polkadot(Origin::signed(target.clone()), origin.clone()),
+			AccountLinkerError::LinkSlotsFull
+		);
+
+		// Replacing a linked address explicitly is still possible
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Replace(0),
+			EXPIRING_BLOCK_NUMBER
+		));
+		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(target.clone()), origin.clone()));
+		assert_eq!(AccountLinker::polkadot_addresses(&origin)[0], target);
+		assert!(AccountLinker::is_polkadot_linked(&target, &origin));
+		assert!(!AccountLinker::is_polkadot_linked(&AccountId32::from([1u8; 32]), &origin));
+		assert_eq!(AccountLinker::polkadot_pending(&origin, &target), None);
+	});
+}
+
+#[test]
+fn test_multiple_polkadot_pending_targets() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let alice: AccountId32 = AccountId32::from([1u8; 32]);
+		let bob: AccountId32 = AccountId32::from([2u8; 32]);
+
+		for target in &[alice.clone(), bob.clone()] {
+			assert_ok!(AccountLinker::link_polkadot(
+				Origin::signed(origin.clone()),
+				target.clone(),
+				LinkMode::Append,
+				EXPIRING_BLOCK_NUMBER
+			));
+		}
+
+		// The second request doesn't overwrite the first one
+		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(alice.clone()), origin.clone()));
+		assert_ok!(AccountLinker::accept_polkadot(Origin::signed(bob.clone()), origin.clone()));
+		assert_eq!(AccountLinker::polkadot_addresses(&origin), vec![alice, bob]);
+	});
+}
+
+#[test]
+fn test_cancel_polkadot_link() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+
+		assert_noop!(
+			AccountLinker::cancel_polkadot_link(Origin::signed(origin.clone()), target.clone()),
+			AccountLinkerError::WrongPendingRequest
+		);
+
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER
+		));
+		assert_ok!(AccountLinker::cancel_polkadot_link(
+			Origin::signed(origin.clone()),
+			target.clone()
+		));
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::PolkadotLinkCancelled(
+				origin.clone(),
+				target.clone()
+			)),]
+		);
+		assert!(!crate::PolkadotPendingExpiry::<Test>::contains_key(
+			EXPIRING_BLOCK_NUMBER,
+			(&origin, &target)
+		));
+
+		assert_noop!(
+			AccountLinker::accept_polkadot(Origin::signed(target), origin),
+			AccountLinkerError::WrongPendingRequest
+		);
+	});
+}
+
+#[test]
+fn test_polkadot_link_request_expiry() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+
+		assert_noop!(
+			AccountLinker::link_polkadot(
+				Origin::signed(origin.clone()),
+				target.clone(),
+				LinkMode::Append,
+				1
+			),
+			AccountLinkerError::LinkRequestExpired
+		);
+		assert_noop!(
+			AccountLinker::link_polkadot(
+				Origin::signed(origin.clone()),
+				target.clone(),
+				LinkMode::Append,
+				ExpiringBlockNumberMax::get() + 1
+			),
+			AccountLinkerError::InvalidExpiringBlockNumber
+		);
+
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			5
+		));
+		// A new request to the same target replaces the previous one
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			3
+		));
+		assert!(!crate::PolkadotPendingExpiry::<Test>::contains_key(5, (&origin, &target)));
+
+		run_to_block(2);
+		assert!(AccountLinker::polkadot_pending(&origin, &target).is_some());
+		events();
+
+		run_to_block(3);
+		assert_eq!(AccountLinker::polkadot_pending(&origin, &target), None);
+		assert!(!crate::PolkadotPendingExpiry::<Test>::contains_key(3, (&origin, &target)));
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::PolkadotLinkExpired(
+				origin.clone(),
+				target.clone()
+			)),]
+		);
+
+		assert_noop!(
+			AccountLinker::accept_polkadot(Origin::signed(target), origin),
+			AccountLinkerError::WrongPendingRequest
+		);
+	});
+}
+
+#[test]
+fn test_expired_polkadot_link_not_accepted() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let target: AccountId32 = AccountId32::from([1u8; 32]);
+
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			3
+		));
+
+		// Expired, but not swept yet
+		System::set_block_number(3);
+		assert_noop!(
+			AccountLinker::accept_polkadot(Origin::signed(target), origin),
+			AccountLinkerError::LinkRequestExpired
+		);
+	});
+}
+
+#[test]
+fn test_polkadot_pending_sweep_limit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let target: AccountId32 = AccountId32::from([0u8; 32]);
+		let count = PendingSweepLimit::get() as u8 * 2;
+		for i in 1..=count {
+			assert_ok!(AccountLinker::link_polkadot(
+				Origin::signed(AccountId32::from([i; 32])),
+				target.clone(),
+				LinkMode::Append,
+				2
+			));
+		}
+
+		// The expired requests are removed over several blocks
+		run_to_block(2);
+		let pending = |n: u8| {
+			(1..=n)
+				.filter(|i| {
+					AccountLinker::polkadot_pending(AccountId32::from([*i; 32]), &target).is_some()
+				})
+				.count()
+		};
+		assert_eq!(pending(count), PendingSweepLimit::get() as usize);
+
+		run_to_block(3);
+		assert_eq!(pending(count), 0);
+	});
+}
+
+#[test]
+fn test_link_polkadot_signed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+
+		let msg = generate_msg(&origin, EXPIRING_BLOCK_NUMBER, 0);
+		let sr25519_target = link_signed(&origin, &sr25519::Pair::from_seed(&[1u8; 32]), &msg);
+		let msg = generate_msg(&origin, EXPIRING_BLOCK_NUMBER, 1);
+		let ed25519_target = link_signed(&origin, &ed25519::Pair::from_seed(&[2u8; 32]), &msg);
+		// Wallets wrap raw payloads before signing them
+		let mut msg = b"<Bytes>".to_vec();
+		msg.append(&mut generate_msg(&origin, EXPIRING_BLOCK_NUMBER, 2));
+		msg.extend_from_slice(b"</Bytes>");
+		let ecdsa_target = link_signed(&origin, &ecdsa::Pair::from_seed(&[3u8; 32]), &msg);
+
+		assert_eq!(
+			AccountLinker::polkadot_addresses(&origin),
+			vec![sr25519_target.clone(), ed25519_target, ecdsa_target]
+		);
+		assert_eq!(AccountLinker::link_nonce(&origin), 3);
+		assert!(AccountLinker::is_polkadot_linked(&sr25519_target, &origin));
+		assert_eq!(
+			events()[0],
+			Event::AccountLinker(crate::Event::PolkadotAddressLinked(origin, sr25519_target))
+		);
+	});
+}
+
+#[test]
+fn test_link_polkadot_signed_invalid_signature() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = sr25519::Pair::from_seed(&[1u8; 32]);
+		let target = MultiSigner::from(pair.public()).into_account();
+		let other = sr25519::Pair::from_seed(&[2u8; 32]);
+
+		let msg = generate_msg(&origin, EXPIRING_BLOCK_NUMBER, 0);
+		assert_noop!(
+			AccountLinker::link_polkadot_signed(
+				Origin::signed(origin.clone()),
+				target.clone(),
+				LinkMode::Append,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				MultiSignature::from(other.sign(&msg))
+			),
+			AccountLinkerError::InvalidPolkadotSignature
+		);
+
+		// The signature is bound to the link nonce
+		let sig = MultiSignature::from(pair.sign(&msg));
+		assert_ok!(AccountLinker::link_polkadot_signed(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			sig.clone()
+		));
+		assert_noop!(
+			AccountLinker::link_polkadot_signed(
+				Origin::signed(origin.clone()),
+				target.clone(),
+				LinkMode::Replace(0),
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sig.clone()
+			),
+			AccountLinkerError::StaleLinkNonce
+		);
+		assert_noop!(
+			AccountLinker::link_polkadot_signed(
+				Origin::signed(origin),
+				target,
+				LinkMode::Replace(0),
+				EXPIRING_BLOCK_NUMBER,
+				1,
+				sig
+			),
+			AccountLinkerError::InvalidPolkadotSignature
+		);
+	});
+}
+
+#[test]
+fn test_link_polkadot_signed_removes_pending() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let origin: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let target = MultiSigner::from(pair.public()).into_account();
+
+		assert_ok!(AccountLinker::link_polkadot(
+			Origin::signed(origin.clone()),
+			target.clone(),
+			LinkMode::Append,
+			EXPIRING_BLOCK_NUMBER
+		));
+		link_signed(&origin, &pair, &generate_msg(&origin, EXPIRING_BLOCK_NUMBER, 0));
+
+		assert_eq!(AccountLinker::polkadot_pending(&origin, &target), None);
+		assert_noop!(
+			AccountLinker::accept_polkadot(Origin::signed(target), origin),
 			AccountLinkerError::WrongPendingRequest
 		);
 	});
@@ -0,0 +1,185 @@
+use crate::{mock::*, LinkExclusivity, LinkMode};
+
+use bitcoin::util::base58;
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok};
+use sp_core::{ed25519, Pair};
+use sp_runtime::AccountId32;
+
+const EXPIRING_BLOCK_NUMBER: u32 = 100;
+
+fn sign_link_message(
+	pair: &ed25519::Pair,
+	account: &AccountId32,
+	block_number: u32,
+	nonce: u32,
+) -> [u8; 64] {
+	let mut payload = link_domain();
+	payload.append(&mut account.encode());
+	payload.append(&mut block_number.encode());
+	payload.append(&mut nonce.encode());
+	let msg = format!("Link Litentry: {}", hex::encode(payload));
+
+	pair.sign(msg.as_bytes()).0
+}
+
+fn link_solana(account: &AccountId32, pair: &ed25519::Pair, mode: LinkMode) {
+	let nonce = AccountLinker::link_nonce(account);
+	assert_ok!(AccountLinker::link_solana(
+		Origin::signed(account.clone()),
+		mode,
+		pair.public().0,
+		EXPIRING_BLOCK_NUMBER,
+		nonce,
+		sign_link_message(pair, account, EXPIRING_BLOCK_NUMBER, nonce)
+	));
+}
+
+#[test]
+fn test_solana_link() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let addr = pair.public().0;
+
+		link_solana(&account, &pair, LinkMode::Append);
+
+		assert_eq!(AccountLinker::solana_addresses(&account), vec![addr]);
+		assert_eq!(AccountLinker::solana_linked_accounts(&addr), vec![account.clone()]);
+		assert!(AccountLinker::is_solana_linked(&addr, &account));
+		assert_eq!(AccountLinker::link_nonce(&account), 1);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::SolanaAddressLinked(
+				account.clone(),
+				base58::encode_slice(&addr).into_bytes()
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_solana_link_wrong_key() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let other = ed25519::Pair::from_seed(&[2u8; 32]);
+
+		assert_noop!(
+			AccountLinker::link_solana(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				other.public().0,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sign_link_message(&pair, &account, EXPIRING_BLOCK_NUMBER, 0)
+			),
+			AccountLinkerError::InvalidSolanaSignature
+		);
+	});
+}
+
+#[test]
+fn test_solana_link_signature_replay() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let sig = sign_link_message(&pair, &account, EXPIRING_BLOCK_NUMBER, 0);
+
+		link_solana(&account, &pair, LinkMode::Append);
+
+		assert_noop!(
+			AccountLinker::link_solana(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				pair.public().0,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sig
+			),
+			AccountLinkerError::StaleLinkNonce
+		);
+	});
+}
+
+#[test]
+fn test_solana_link_by_third_party() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let victim: AccountId32 = AccountId32::from([1u8; 32]);
+		let mallory: AccountId32 = AccountId32::from([2u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		LinkDeposit::set(&10);
+		Balances::make_free_balance_be(&victim, 100);
+
+		// The signature of the victim's link message is only valid for the victim's origin
+		assert_noop!(
+			AccountLinker::link_solana(
+				Origin::signed(mallory.clone()),
+				LinkMode::Append,
+				pair.public().0,
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				sign_link_message(&pair, &victim, EXPIRING_BLOCK_NUMBER, 0)
+			),
+			AccountLinkerError::InvalidSolanaSignature
+		);
+		assert!(AccountLinker::solana_addresses(&victim).is_empty());
+		assert!(AccountLinker::solana_addresses(&mallory).is_empty());
+		assert_eq!(AccountLinker::link_nonce(&victim), 0);
+		assert_eq!(Balances::reserved_balance(&victim), 0);
+	});
+}
+
+#[test]
+fn test_solana_unlink() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([0u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let addr = pair.public().0;
+
+		link_solana(&account, &pair, LinkMode::Append);
+		assert_ok!(AccountLinker::unlink_solana(Origin::signed(account.clone()), addr));
+
+		assert!(AccountLinker::solana_addresses(&account).is_empty());
+		assert!(!AccountLinker::is_solana_linked(&addr, &account));
+		assert_noop!(
+			AccountLinker::unlink_solana(Origin::signed(account.clone()), addr),
+			AccountLinkerError::LinkNotFound
+		);
+	});
+}
+
+#[test]
+fn test_solana_link_exclusivity_transfer() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		Exclusivity::set(&LinkExclusivity::Transfer);
+
+		let alice: AccountId32 = AccountId32::from([0u8; 32]);
+		let bob: AccountId32 = AccountId32::from([1u8; 32]);
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let addr = pair.public().0;
+
+		link_solana(&alice, &pair, LinkMode::Append);
+		link_solana(&bob, &pair, LinkMode::Append);
+
+		assert!(AccountLinker::solana_addresses(&alice).is_empty());
+		assert_eq!(AccountLinker::solana_addresses(&bob), vec![addr]);
+		assert_eq!(AccountLinker::solana_linked_accounts(&addr), vec![bob.clone()]);
+		assert!(events().contains(&Event::AccountLinker(crate::Event::SolanaAddressTransferred(
+			alice,
+			bob,
+			base58::encode_slice(&addr).into_bytes()
+		))));
+	});
+}
@@ -0,0 +1,124 @@
+use crate::{mock::*, LinkMode};
+
+use bitcoin::util::base58;
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok};
+use parity_crypto::{
+	publickey::{sign, Generator, KeyPair, Message, Random},
+	Keccak256,
+};
+use sp_runtime::AccountId32;
+
+const EXPIRING_BLOCK_NUMBER: u32 = 100;
+
+fn generate_msg(prefix: &[u8], account: &AccountId32, block_number: u32, nonce: u32) -> Message {
+	let mut bytes = prefix.to_vec();
+	bytes.extend_from_slice(b"88Link Litentry: ");
+	bytes.append(&mut link_domain());
+	bytes.append(&mut account.encode());
+	bytes.append(&mut block_number.encode());
+	bytes.append(&mut nonce.encode());
+
+	Message::from(bytes.keccak256())
+}
+
+fn generate_sig(key_pair: &KeyPair, msg: &Message) -> [u8; 65] {
+	sign(key_pair.secret(), &msg).unwrap().into_electrum()
+}
+
+fn tron_address(addr: &[u8; 20]) -> Vec<u8> {
+	let mut versioned = vec![0x41];
+	versioned.extend_from_slice(addr);
+	base58::check_encode_slice(&versioned).into_bytes()
+}
+
+#[test]
+fn test_tron_link() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([5u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		let msg = generate_msg(b"\x19TRON Signed Message:\n", &account, EXPIRING_BLOCK_NUMBER, 0);
+		assert_ok!(AccountLinker::link_tron(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr,
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			generate_sig(&key_pair, &msg)
+		));
+
+		assert_eq!(AccountLinker::tron_addresses(&account), vec![addr]);
+		assert_eq!(AccountLinker::tron_linked_accounts(&addr), vec![account.clone()]);
+		assert!(AccountLinker::eth_addresses(&account).is_empty());
+		assert_eq!(AccountLinker::link_nonce(&account), 1);
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::TronAddressLinked(
+				account.clone(),
+				tron_address(&addr)
+			)),]
+		);
+	});
+}
+
+#[test]
+fn test_tron_link_ethereum_signature() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([5u8; 32]);
+		let key_pair = Random {}.generate();
+
+		// A `personal_sign` signature for `link_eth` can't be used to link a Tron address
+		let msg =
+			generate_msg(b"\x19Ethereum Signed Message:\n", &account, EXPIRING_BLOCK_NUMBER, 0);
+		assert_noop!(
+			AccountLinker::link_tron(
+				Origin::signed(account.clone()),
+				LinkMode::Append,
+				key_pair.address().to_fixed_bytes(),
+				EXPIRING_BLOCK_NUMBER,
+				0,
+				generate_sig(&key_pair, &msg)
+			),
+			AccountLinkerError::UnexpectedAddress
+		);
+	});
+}
+
+#[test]
+fn test_tron_unlink() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+
+		let account: AccountId32 = AccountId32::from([5u8; 32]);
+		let key_pair = Random {}.generate();
+		let addr = key_pair.address().to_fixed_bytes();
+
+		let msg = generate_msg(b"\x19TRON Signed Message:\n", &account, EXPIRING_BLOCK_NUMBER, 0);
+		assert_ok!(AccountLinker::link_tron(
+			Origin::signed(account.clone()),
+			LinkMode::Append,
+			addr,
+			EXPIRING_BLOCK_NUMBER,
+			0,
+			generate_sig(&key_pair, &msg)
+		));
+		events();
+
+		assert_ok!(AccountLinker::unlink_tron(Origin::signed(account.clone()), addr));
+		assert!(AccountLinker::tron_addresses(&account).is_empty());
+		assert!(!AccountLinker::is_tron_linked(&addr, &account));
+		assert_eq!(
+			events(),
+			[Event::AccountLinker(crate::Event::TronAddressUnlinked(
+				account.clone(),
+				tron_address(&addr)
+			)),]
+		);
+	});
+}
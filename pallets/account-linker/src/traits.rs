@@ -0,0 +1,85 @@
+//! Traits for other pallets to consume the links of the account linker through associated types
+//! of their `Config`, rather than depending on `Config` of the account linker
+
+use crate::{EthAddress, EthContractLinkRequest, LinkedAddress, SolanaAddress};
+use frame_support::traits::Get;
+use sp_runtime::{traits::Member, DispatchError};
+use sp_std::prelude::*;
+
+/// The addresses linked to the Litentry accounts, in slot order
+pub trait LinkedAccountsProvider<AccountId> {
+	/// The Ethereum addresses linked to `account`
+	fn eth_addresses(account: &AccountId) -> Vec<EthAddress>;
+
+	/// The Ethereum addresses linked to `account` that aren't stale
+	fn fresh_eth_addresses(account: &AccountId) -> Vec<EthAddress>;
+
+	/// The BTC addresses linked to `account`
+	fn btc_addresses(account: &AccountId) -> Vec<Vec<u8>>;
+
+	/// The Tron addresses linked to `account`
+	fn tron_addresses(account: &AccountId) -> Vec<EthAddress>;
+
+	/// The Solana addresses linked to `account`
+	fn solana_addresses(account: &AccountId) -> Vec<SolanaAddress>;
+
+	/// The Cosmos SDK addresses linked to `account`
+	fn cosmos_addresses(account: &AccountId) -> Vec<Vec<u8>>;
+
+	/// The Litentry addresses linked to `account`
+	fn polkadot_addresses(account: &AccountId) -> Vec<AccountId>;
+
+	/// Whether the link of `addr` to `account` wasn't refreshed within its validity period
+	fn is_link_stale(account: &AccountId, addr: &LinkedAddress<AccountId>) -> bool;
+}
+
+/// The pending `link_eth_contract` requests, attested by the offchain workers
+pub trait ContractLinkAttestation<AccountId: Member, BlockNumber: Member> {
+	/// The maximum length of the signature of a request
+	type MaxSignatureLen: Get<u32>;
+
+	/// The number of attestations that resolve a request
+	type Quorum: Get<u32>;
+
+	/// The most accounts a contract is transferred from when an attestation links it
+	type MaxDisplacedAccounts: Get<u32>;
+
+	/// All the pending requests, with the account that sent them and the contract to link
+	fn pending_requests() -> Vec<(
+		AccountId,
+		EthAddress,
+		EthContractLinkRequest<AccountId, BlockNumber, Self::MaxSignatureLen, Self::Quorum>,
+	)>;
+
+	/// Record whether `contract` accepts the signature of the pending request of `account`,
+	/// returns the number of accounts the contract was transferred from when it was linked
+	fn attest(
+		attester: AccountId,
+		account: AccountId,
+		contract: EthAddress,
+		valid: bool,
+	) -> Result<u32, DispatchError>;
+
+	/// Allow `attester` to attest and add a pending request of `account` to link `contract`
+	/// that the approval of `attester` completes, replacing the last of its full slots and
+	/// transferring `contract` from `displaced` other accounts: the worst case of `attest`
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_attestable_request(
+		attester: &AccountId,
+		account: &AccountId,
+		contract: EthAddress,
+		displaced: u32,
+	);
+}
+
+/// Notified when an address is linked to or unlinked from a Litentry account.
+///
+/// The handlers run within the link and unlink calls, whose benchmarked weights include them.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait OnLinkChanged<AccountId> {
+	/// `addr` was linked to `account`
+	fn on_linked(_account: &AccountId, _addr: &LinkedAddress<AccountId>) {}
+
+	/// `addr` is no longer linked to `account`
+	fn on_unlinked(_account: &AccountId, _addr: &LinkedAddress<AccountId>) {}
+}
@@ -1,6 +1,15 @@
-use codec::Encode;
+use crate::btc::{base58::ToBase58, legacy::versioned_hash_with_checksum};
 use sp_std::prelude::*;
 
+/// Prefix of the messages signed with `personal_sign` by Ethereum wallets
+pub const ETH_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// Prefix of the messages signed with `signMessageV2` by Tron wallets
+pub const TRON_MESSAGE_PREFIX: &[u8] = b"\x19TRON Signed Message:\n";
+
+/// Version byte of Tron addresses
+const TRON_ADDRESS_VERSION: u8 = 0x41;
+
 pub fn addr_from_sig(msg: [u8; 32], sig: [u8; 65]) -> Result<[u8; 20], sp_io::EcdsaVerifyError> {
 	let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg)?;
 	let hashed_pk = sp_io::hashing::keccak_256(&pubkey);
@@ -13,48 +22,100 @@ pub fn addr_from_sig(msg: [u8; 32], sig: [u8; 65]) -> Result<[u8; 20], sp_io::Ec
 /// Returns a eth_sign-compatible hash of data to sign.
 /// The data is prefixed with special message to prevent
 /// malicious DApps from using the function to sign forged transactions.
-pub fn eth_data_hash(mut data: Vec<u8>) -> Result<[u8; 32], &'static str> {
-	const MSG_LEN: usize = 51;
-	if data.len() != MSG_LEN {
-		log::error!(
-			"Ethereum message has an unexpected length {} !!! Expected is {}.",
-			data.len(),
-			MSG_LEN
-		);
-		return Err("Unexpected ethereum message length!")
-	}
-	let mut length_bytes = usize_to_u8_array(data.len())?;
-	let mut eth_data = b"\x19Ethereum Signed Message:\n".encode();
+pub fn eth_data_hash(data: Vec<u8>) -> [u8; 32] {
+	signed_data_hash(ETH_MESSAGE_PREFIX, data)
+}
+
+/// Returns the hash of data to sign for wallets of EVM-derived chains, which only differ from
+/// eth_sign by the prefix, e.g. `TRON_MESSAGE_PREFIX`.
+pub fn signed_data_hash(prefix: &[u8], mut data: Vec<u8>) -> [u8; 32] {
+	let mut length_bytes = usize_to_u8_array(data.len());
+	let mut eth_data = prefix.to_vec();
 	eth_data.append(&mut length_bytes);
 	eth_data.append(&mut data);
-	Ok(sp_io::hashing::keccak_256(&eth_data))
+	sp_io::hashing::keccak_256(&eth_data)
+}
+
+/// Returns the Base58Check encoded Tron address of the 20 bytes address derived from a key,
+/// the same one as its Ethereum address.
+pub fn tron_address(addr: &[u8; 20]) -> Vec<u8> {
+	versioned_hash_with_checksum(TRON_ADDRESS_VERSION, addr).to_base58()
+}
+
+/// EIP-712 encoded type of the signing domain
+const EIP712_DOMAIN_TYPE: &[u8] =
+	b"EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)";
+
+/// EIP-712 encoded type of the link message
+const EIP712_LINK_TYPE: &[u8] = b"Link(bytes account,uint256 expiringBlockNumber,uint256 nonce)";
+
+/// Returns the EIP-712 domain separator for the given domain fields.
+pub fn eip712_domain_separator(
+	name: &[u8],
+	version: &[u8],
+	chain_id: u64,
+	salt: [u8; 32],
+) -> [u8; 32] {
+	let mut data = Vec::with_capacity(32 * 5);
+	data.extend_from_slice(&sp_io::hashing::keccak_256(EIP712_DOMAIN_TYPE));
+	data.extend_from_slice(&sp_io::hashing::keccak_256(name));
+	data.extend_from_slice(&sp_io::hashing::keccak_256(version));
+	data.extend_from_slice(&u128_to_uint256(chain_id as u128));
+	data.extend_from_slice(&salt);
+	sp_io::hashing::keccak_256(&data)
+}
+
+/// Returns the EIP-712 `hashStruct` of a `Link` message.
+pub fn eip712_link_struct_hash(
+	account: &[u8],
+	expiring_block_number: u128,
+	nonce: u32,
+) -> [u8; 32] {
+	let mut data = Vec::with_capacity(32 * 4);
+	data.extend_from_slice(&sp_io::hashing::keccak_256(EIP712_LINK_TYPE));
+	data.extend_from_slice(&sp_io::hashing::keccak_256(account));
+	data.extend_from_slice(&u128_to_uint256(expiring_block_number));
+	data.extend_from_slice(&u128_to_uint256(nonce as u128));
+	sp_io::hashing::keccak_256(&data)
+}
+
+/// Returns the EIP-712 typed data hash to sign, i.e.
+/// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+pub fn eip712_hash(domain_separator: [u8; 32], struct_hash: [u8; 32]) -> [u8; 32] {
+	let mut data = Vec::with_capacity(2 + 32 * 2);
+	data.extend_from_slice(b"\x19\x01");
+	data.extend_from_slice(&domain_separator);
+	data.extend_from_slice(&struct_hash);
+	sp_io::hashing::keccak_256(&data)
+}
+
+/// Encode an unsigned integer as a big-endian ABI `uint256` word.
+fn u128_to_uint256(value: u128) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[16..].copy_from_slice(&value.to_be_bytes());
+	word
 }
 
 /// Convert a usize type to a u8 array.
 /// The input is first converted as a string with decimal presentation,
 /// and then this string is converted to a byte array with UTF8 encoding.
-/// To avoid unnecessary complexity, the current function supports up to
-/// 2 digits unsigned decimal (range 0 - 99)
-fn usize_to_u8_array(length: usize) -> Result<Vec<u8>, &'static str> {
-	if length >= 100 {
-		Err("Unexpected ethereum message length!")
-	} else {
-		let digits = b"0123456789".encode();
-		let tens = length / 10;
-		let ones = length % 10;
-
-		let mut vec_res: Vec<u8> = Vec::new();
-		if tens != 0 {
-			vec_res.push(digits[tens]);
+fn usize_to_u8_array(mut length: usize) -> Vec<u8> {
+	let mut vec_res: Vec<u8> = Vec::new();
+	loop {
+		vec_res.push(b'0' + (length % 10) as u8);
+		length /= 10;
+		if length == 0 {
+			break
 		}
-		vec_res.push(digits[ones]);
-		Ok(vec_res)
 	}
+	vec_res.reverse();
+	vec_res
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use codec::Encode;
 	use hex::decode;
 
 	// A test helper function to add ethereum prefix before message hashing
@@ -116,23 +177,34 @@ mod tests {
 	}
 
 	#[test]
-	fn msg_with_unexpected_length() {
-		let msg = b"Link Litentry: 0123456789abcdef0123456789abcdef999".encode();
-		assert_eq!(Err("Unexpected ethereum message length!"), eth_data_hash(msg));
+	fn msg_hash() {
+		let msg = b"Link Litentry: 0123456789abcdef0123456789abcdef99999999".encode();
+		let res = eth_data_hash(msg.clone());
+		assert_eq!(eth_data_hash_test_helper(msg), res);
+	}
+
+	// 20 bytes account and u32 block number
+	#[test]
+	fn short_msg() {
+		let msg = b"Link Litentry: 0123456789abcdef0123".encode();
+		let res = eth_data_hash(msg.clone());
+		assert_eq!(eth_data_hash_test_helper(msg), res);
 	}
 
+	// Message lengths with 3 digits
 	#[test]
-	fn msg_with_expected_length() {
-		let msg = b"Link Litentry: 0123456789abcdef0123456789abcdef9999".encode();
-		let res = eth_data_hash(msg.clone()).ok().unwrap();
+	fn long_msg() {
+		let mut msg = b"Link Litentry: ".to_vec();
+		msg.extend_from_slice(&[7u8; 100]);
+		let res = eth_data_hash(msg.clone());
 		assert_eq!(eth_data_hash_test_helper(msg), res);
 	}
 
 	// Test input with more than 2 digits
 	#[test]
-	fn usize_to_u8_array_input_too_large() {
+	fn usize_to_u8_array_input_three_digits() {
 		let len: usize = 105;
-		assert_eq!(Err("Unexpected ethereum message length!"), usize_to_u8_array(len))
+		assert_eq!(b"105".to_vec(), usize_to_u8_array(len))
 	}
 
 	// Test inputs with one and two digits respectively
@@ -142,12 +214,74 @@ mod tests {
 	#[test]
 	fn usize_to_u8_array_input_one_digit() {
 		let len: usize = 4;
-		assert_eq!(Ok(vec![52]), usize_to_u8_array(len))
+		assert_eq!(vec![52], usize_to_u8_array(len))
+	}
+
+	// Example from https://eips.ethereum.org/EIPS/eip-712
+	#[test]
+	fn eip712_hash_mail_example() {
+		let mut domain_separator = [0u8; 32];
+		domain_separator.copy_from_slice(
+			&decode("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f").unwrap(),
+		);
+		let mut struct_hash = [0u8; 32];
+		struct_hash.copy_from_slice(
+			&decode("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e").unwrap(),
+		);
+
+		assert_eq!(
+			eip712_hash(domain_separator, struct_hash).to_vec(),
+			decode("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2").unwrap()
+		);
+	}
+
+	// Computed outside the runtime with OpenSSL's KECCAK-256 and the EIP-712 `encodeData` of the
+	// domain, the same computation reproduces the domain separator of the EIP-712 Mail example
+	#[test]
+	fn eip712_domain_separator_vectors() {
+		let hash = |hex: &str| {
+			let mut hash = [0u8; 32];
+			hash.copy_from_slice(&decode(hex).unwrap());
+			hash
+		};
+
+		assert_eq!(
+			eip712_domain_separator(b"AccountLinker", b"1", 42, [69u8; 32]),
+			hash("155044a97c4336a654a25fe46908568e106bc0110221bff2831edeafa5bfe8c1")
+		);
+
+		let mut salt = [0u8; 32];
+		for (i, byte) in salt.iter_mut().enumerate() {
+			*byte = i as u8;
+		}
+		assert_eq!(
+			eip712_domain_separator(b"AccountLinker", b"1", 0x1234_5678, salt),
+			hash("46f0462488a58f172d683d645bb5adba82d5699d365c11e642fbcd562d43c0c3")
+		);
+	}
+
+	#[test]
+	fn msg_with_tron_prefix() {
+		let msg = b"Link Litentry: 0123456789abcdef0123456789abcdef99999999".encode();
+		let mut message_data = b"\x19TRON Signed Message:\n55".to_vec();
+		message_data.extend_from_slice(&msg);
+		assert_eq!(
+			signed_data_hash(TRON_MESSAGE_PREFIX, msg),
+			sp_io::hashing::keccak_256(&message_data)
+		);
+	}
+
+	// Example from https://developers.tron.network/docs/account
+	#[test]
+	fn correct_tron_address() {
+		let mut addr = [0u8; 20];
+		addr.copy_from_slice(&decode("8840E6C55B9ADA326D211D818C34A994AECED808").unwrap());
+		assert_eq!(tron_address(&addr), b"TNPeeaaFB7K9cmo4uQpcU32zGK8G1NYqeL".to_vec());
 	}
 
 	#[test]
 	fn usize_to_u8_array_input_two_digits() {
 		let len: usize = 40;
-		assert_eq!(Ok(vec![52, 48]), usize_to_u8_array(len))
+		assert_eq!(vec![52, 48], usize_to_u8_array(len))
 	}
 }
@@ -15,13 +15,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_account_linker
+//! Weights for pallet_account_linker
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
-//! DATE: 2021-02-25, STEPS: [20, ], REPEAT: 50, LOW RANGE: [], HIGH RANGE: []
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//! PLACEHOLDER WEIGHTS, THIS FILE WAS NOT GENERATED BY THE SUBSTRATE BENCHMARK CLI.
+//! The weights of `link_eth`, `link_btc`, `link_polkadot` and `accept_polkadot` start from a
+//! benchmark run of 2021-02-25, made before these calls changed, the other ones are estimates.
+//! Regenerate the file before relying on them, with:
 
-// Executed Command:
 // target/release/litentry-node
 // benchmark
 // --chain=dev
@@ -46,53 +46,312 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_account_linker.
 pub trait WeightInfo {
-	fn link_eth() -> Weight;
-	fn link_btc() -> Weight;
+	fn link_eth(d: u32) -> Weight;
+	fn link_eth_eip712(d: u32) -> Weight;
+	fn link_eth_contract() -> Weight;
+	fn cancel_eth_contract_link() -> Weight;
+	fn link_btc(d: u32) -> Weight;
+	fn link_tron(d: u32) -> Weight;
+	fn link_solana(d: u32) -> Weight;
+	fn link_cosmos(d: u32) -> Weight;
 	fn link_polkadot() -> Weight;
 	fn accept_polkadot() -> Weight;
+	fn cancel_polkadot_link() -> Weight;
+	fn link_polkadot_signed() -> Weight;
+	fn unlink_eth() -> Weight;
+	fn unlink_btc() -> Weight;
+	fn unlink_tron() -> Weight;
+	fn unlink_solana() -> Weight;
+	fn unlink_cosmos() -> Weight;
+	fn unlink_polkadot() -> Weight;
+	fn set_link_label() -> Weight;
+	fn refresh_link_eth() -> Weight;
+	fn refresh_link_btc() -> Weight;
+	fn refresh_link_tron() -> Weight;
+	fn refresh_link_solana() -> Weight;
+	fn refresh_link_cosmos() -> Weight;
+	fn refresh_link_polkadot() -> Weight;
 }
 
 /// Weights for pallet_account_linker using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn link_eth() -> Weight {
+	fn link_eth(d: u32) -> Weight {
 		(324_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_eth_eip712(d: u32) -> Weight {
+		(331_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_eth_contract() -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
-	fn link_btc() -> Weight {
+	fn cancel_eth_contract_link() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn link_btc(d: u32) -> Weight {
 		(335_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_tron(d: u32) -> Weight {
+		(324_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_solana(d: u32) -> Weight {
+		(318_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_cosmos(d: u32) -> Weight {
+		(346_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
 	}
 	fn link_polkadot() -> Weight {
-		(335_000_000 as Weight).saturating_add(T::DbWeight::get().reads(1 as Weight))
+		(335_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
 	}
 	fn accept_polkadot() -> Weight {
 		(335_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn cancel_polkadot_link() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn link_polkadot_signed() -> Weight {
+		(335_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	fn unlink_eth() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_btc() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_tron() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_solana() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_cosmos() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_polkadot() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn set_link_label() -> Weight {
+		(30_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	fn refresh_link_eth() -> Weight {
+		(318_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_btc() -> Weight {
+		(912_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_tron() -> Weight {
+		(318_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_solana() -> Weight {
+		(104_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_cosmos() -> Weight {
+		(641_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_polkadot() -> Weight {
+		(637_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn link_eth() -> Weight {
+	fn link_eth(d: u32) -> Weight {
 		(324_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_eth_eip712(d: u32) -> Weight {
+		(331_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_eth_contract() -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
-	fn link_btc() -> Weight {
+	fn cancel_eth_contract_link() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn link_btc(d: u32) -> Weight {
 		(335_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_tron(d: u32) -> Weight {
+		(324_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_solana(d: u32) -> Weight {
+		(318_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
+	fn link_cosmos(d: u32) -> Weight {
+		(346_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
 	}
 	fn link_polkadot() -> Weight {
-		(335_000_000 as Weight).saturating_add(RocksDbWeight::get().reads(1 as Weight))
+		(335_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
 	}
 	fn accept_polkadot() -> Weight {
 		(335_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn cancel_polkadot_link() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn link_polkadot_signed() -> Weight {
+		(335_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn unlink_eth() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_btc() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_tron() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_solana() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_cosmos() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn unlink_polkadot() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn set_link_label() -> Weight {
+		(30_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
+	fn refresh_link_eth() -> Weight {
+		(318_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_btc() -> Weight {
+		(912_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_tron() -> Weight {
+		(318_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_solana() -> Weight {
+		(104_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_cosmos() -> Weight {
+		(641_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn refresh_link_polkadot() -> Weight {
+		(637_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
 }
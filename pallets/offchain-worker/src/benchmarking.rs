@@ -9,6 +9,7 @@ use frame_system::RawOrigin;
 use sp_std::prelude::*;
 
 use crate::Pallet as OCW;
+use account_linker::ContractLinkAttestation;
 
 benchmarks! {
 
@@ -26,6 +27,15 @@ benchmarks! {
         
     }: submit_balance(RawOrigin::Signed(caller), account_id, block_number.into(), data_source.into(), balance)
 
+    submit_contract_link_attestation {
+        let d in 0 .. <T::ContractLinks as ContractLinkAttestation<T::AccountId, T::BlockNumber>>::MaxDisplacedAccounts::get();
+        let caller: T::AccountId = account("caller", 0, 0);
+        let account_id: T::AccountId = account("Alice", 0, 0);
+        let contract = [7u8; 20];
+        // the approval that reaches the quorum and links the contract, transferring it from d accounts
+        T::ContractLinks::add_attestable_request(&caller, &account_id, contract, d);
+    }: submit_contract_link_attestation(RawOrigin::Signed(caller), account_id, contract, true)
+
     on_finalize {
         let caller: T::AccountId = account("caller", 0, 0);
 
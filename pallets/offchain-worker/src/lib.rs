@@ -4,6 +4,14 @@
 //! from most popular websire like etherscan, infura and blockinfo. After get the balance, Offchain worker emit the event
 //! with balance info and store them on chain for on-chain query.
 //!
+//! The linked accounts are read through the `LinkedAccounts` and `ContractLinks` types of `Config`, which the runtime
+//! sets to account linker pallet, so the pallet can be tested against mocked links.
+//!
+//! ## Smart contract wallets
+//! Offchain worker also calls the EIP-1271 `isValidSignature` method of the contracts of pending `link_eth_contract`
+//! requests in account linker pallet via infura, and submits whether the signature is valid. The offchain worker
+//! accounts have to be the `ContractLinkAttesters` of account linker.
+//!
 //! ## API token
 //! The offchain worker need the API token to query data from third party data provider. Currently, offchain worker get
 //! the API tokens from a local server. Then store the API tokens in offchain worder local storage.
@@ -27,6 +35,8 @@ pub mod benchmarking;
 mod tests;
 
 const TOKEN_SERVER_URL: &str = "http://127.0.0.1:4000";
+// Blocks an attestation waits to be included before it is submitted again
+const ATTESTATION_RESUBMIT_PERIOD: u32 = 10;
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"ocw!");
 
 #[frame_support::pallet]
@@ -68,6 +78,7 @@ pub mod pallet {
 	}
 
 	use crate::*;
+	use account_linker::{ContractLinkAttestation, LinkedAccountsProvider};
 	use core::convert::TryInto;
 	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
 	use frame_system::{
@@ -87,7 +98,7 @@ pub mod pallet {
 	};
 	use sp_runtime::{
 		offchain::storage::StorageValueRef,
-		traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member},
+		traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, Saturating},
 	};
 	use weights::WeightInfo;
 
@@ -96,9 +107,7 @@ pub mod pallet {
 	>>::PositiveImbalance;
 
 	#[pallet::config]
-	pub trait Config:
-		frame_system::Config + account_linker::Config + CreateSignedTransaction<Call<Self>>
-	{
+	pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
 		type Balance: Parameter
 			+ Member
 			+ AtLeast32BitUnsigned
@@ -125,6 +134,12 @@ pub mod pallet {
 		/// The weights used is depends on the data size in CommitAccountBalance, as a reference, the weights for 100 items can be found
 		/// in weights.rs dummy() -> Weight. need re-run the benchmarking according to this parameter and guarantee the data is reasonable
 		type MaximumCommitsPerSession: Get<u32>;
+
+		/// The linked external accounts whose balances are queried, e.g. `account_linker::Pallet<Runtime>`
+		type LinkedAccounts: LinkedAccountsProvider<Self::AccountId>;
+
+		/// The pending `link_eth_contract` requests to attest, e.g. `account_linker::Pallet<Runtime>`
+		type ContractLinks: ContractLinkAttestation<Self::AccountId, Self::BlockNumber>;
 	}
 
 	#[pallet::hooks]
@@ -337,6 +352,35 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Offchain worker submit whether a contract accepts the EIP-1271 signature of a pending
+		/// `link_eth_contract` request of the account linker.
+		///
+		/// Extrinsic Arguments.
+		/// account: the account that requested the link.
+		/// contract: the Ethereum contract to link.
+		/// valid: whether `isValidSignature` of the contract returned the magic value.
+		///
+		/// The dispatch origin for this call must be one of the account linker's
+		/// `ContractLinkAttesters`.
+		///
+		/// Charged for transferring the contract from the most accounts, the weight of the
+		/// accounts it wasn't transferred from is refunded.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::submit_contract_link_attestation(
+			<T::ContractLinks as ContractLinkAttestation<T::AccountId, T::BlockNumber>>::MaxDisplacedAccounts::get()
+		))]
+		pub fn submit_contract_link_attestation(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			contract: [u8; 20],
+			valid: bool,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let displaced = T::ContractLinks::attest(sender, account, contract, valid)?;
+
+			Ok(Some(<T as pallet::Config>::WeightInfo::submit_contract_link_attestation(displaced)).into())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -454,6 +498,7 @@ pub mod pallet {
 				Ok(Some(token)) => {
 					log::info!("API keys found! Start to query from sources.");
 					Self::query(block_number, &token);
+					Self::attest_contract_links(block_number, &token);
 					Ok(())
 				},
 				Ok(None) => {
@@ -764,7 +809,7 @@ pub mod pallet {
 						};
 
 						Self::fetch_balances(
-							<account_linker::Pallet<T>>::eth_addresses(account),
+							T::LinkedAccounts::fresh_eth_addresses(account),
 							urls::HttpRequest::GET(get),
 							&urls::parse_etherscan_balances,
 						)
@@ -790,7 +835,7 @@ pub mod pallet {
 							api_token: token,
 						};
 						Self::fetch_balances(
-							<account_linker::Pallet<T>>::eth_addresses(account),
+							T::LinkedAccounts::fresh_eth_addresses(account),
 							urls::HttpRequest::POST(post),
 							&urls::parse_blockchain_info_balances,
 						)
@@ -830,6 +875,118 @@ pub mod pallet {
 			}
 		}
 
+		// Attest the EIP-1271 signatures of the pending `link_eth_contract` requests
+		fn attest_contract_links(block_number: T::BlockNumber, info: &urls::TokenInfo) {
+			let token = match core::str::from_utf8(&info.infura) {
+				Ok(token) if token.len() > 0 => token,
+				_ => return,
+			};
+
+			// Skip the requests this ocw already attested
+			let offchain_worker_account = StorageValueRef::persistent(b"offchain-worker::account");
+			let local_account = match offchain_worker_account.get::<T::AccountId>() {
+				Ok(Some(account)) => Some(account),
+				_ => None,
+			};
+
+			// and the ones whose attestation was submitted recently, but isn't included yet
+			let offchain_worker_attestations =
+				StorageValueRef::persistent(b"offchain-worker::attestations");
+			let submitted: Vec<(T::AccountId, [u8; 20], [u8; 32], T::BlockNumber)> =
+				match offchain_worker_attestations.get() {
+					Ok(Some(submitted)) => submitted,
+					_ => Vec::new(),
+				};
+			let resubmit_after = block_number.saturating_sub(ATTESTATION_RESUBMIT_PERIOD.into());
+			// only the attestations of requests that are still pending are kept
+			let mut pending = Vec::new();
+
+			for (account, contract, request) in T::ContractLinks::pending_requests() {
+				if let Some(local_account) = &local_account {
+					if request.approvals.contains(local_account) ||
+						request.rejections.contains(local_account)
+					{
+						continue
+					}
+				}
+
+				if let Some(attestation) = submitted.iter().find(|(a, c, hash, at)| {
+					*a == account && *c == contract && *hash == request.hash && *at > resubmit_after
+				}) {
+					pending.push(attestation.clone());
+					continue
+				}
+
+				// An expired request is dropped by any attestation, no need to query the contract
+				let valid = if request.expiring_block_number <= block_number {
+					Some(false)
+				} else {
+					Self::is_valid_contract_signature(
+						&contract,
+						&request.hash,
+						&request.signature,
+						token,
+					)
+				};
+
+				if let Some(valid) = valid {
+					if Self::offchain_attestation_tx(account.clone(), contract, valid) {
+						pending.push((account, contract, request.hash, block_number));
+					}
+				}
+			}
+
+			offchain_worker_attestations.set(&pending);
+		}
+
+		// Call `isValidSignature` of the contract via infura
+		fn is_valid_contract_signature(
+			contract: &[u8; 20],
+			hash: &[u8; 32],
+			signature: &[u8],
+			token: &str,
+		) -> Option<bool> {
+			let mut link: Vec<u8> = Vec::new();
+			link.extend("https://ropsten.infura.io/v3/".as_bytes());
+			link.extend(token.as_bytes());
+
+			let body = urls::eip1271_call_body(contract, hash, signature);
+			let result = urls::fetch_json_http_post(&link[..], &body[..]).ok()?;
+			let response = sp_std::str::from_utf8(&result).ok()?;
+
+			urls::parse_eip1271_response(response)
+		}
+
+		// Sign the attestation of a `link_eth_contract` request, returns whether it was submitted
+		fn offchain_attestation_tx(account: T::AccountId, contract: [u8; 20], valid: bool) -> bool {
+			log::info!(
+				"ocw sign attestation: account {:?}, contract {:?}, valid {:?}",
+				account.clone(),
+				contract,
+				valid
+			);
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+
+			let result = signer.send_signed_transaction(|_acct|
+				Call::submit_contract_link_attestation(account.clone(), contract, valid));
+
+			if let Some((acc, res)) = result {
+				if res.is_err() {
+					log::error!("failure: offchain_attestation_tx: tx sent: {:?}", acc.id);
+				} else {
+					log::info!("successful: offchain_attestation_tx: tx sent: {:?}", acc.id);
+				}
+
+				// Record the account in local storage then we can skip the attested requests
+				let account = StorageValueRef::persistent(b"offchain-worker::account");
+				account.set(&acc.id);
+				res.is_ok()
+			} else {
+				log::error!("No local account available");
+				false
+			}
+		}
+
 		// Sign the query result
 		fn offchain_signed_tx(
 			account: T::AccountId,
@@ -120,9 +120,69 @@ where
 	}
 }
 
+parameter_types! {
+	pub const EthChainId: u64 = 42;
+	pub const BtcNetwork: account_linker::BitcoinNetwork = account_linker::BitcoinNetwork::Mainnet;
+	pub const Exclusivity: account_linker::LinkExclusivity = account_linker::LinkExclusivity::Shared;
+	pub const MaxDisplacedAccounts: u32 = 10;
+	pub const MaxEthLinks: u32 = 3;
+	pub const MaxBtcLinks: u32 = 3;
+	pub const MaxTronLinks: u32 = 3;
+	pub const MaxSolanaLinks: u32 = 3;
+	pub const MaxCosmosLinks: u32 = 3;
+	pub CosmosHrp: Vec<u8> = b"cosmos".to_vec();
+	pub const MaxPolkadotLinks: u32 = 3;
+	pub const ExpiringBlockNumberMax: u64 = 10 * 60 * 24 * 30;
+	pub const PendingSweepLimit: u32 = 10;
+	pub const LinkDeposit: u128 = 0;
+	pub const PendingDeposit: u128 = 0;
+	pub const ContractLinkQuorum: u32 = 1;
+	pub const MaxContractSignatureLen: u32 = 1024;
+	pub const MaxLinkLabelLen: u32 = 32;
+	pub const LinkValidityPeriod: Option<u64> = None;
+	pub const StaleSweepLimit: u32 = 10;
+}
+
+pub struct ContractLinkAttesters;
+impl frame_support::traits::SortedMembers<AccountId> for ContractLinkAttesters {
+	fn sorted_members() -> Vec<AccountId> {
+		vec![]
+	}
+
+	fn contains(_who: &AccountId) -> bool {
+		true
+	}
+}
+
 impl account_linker::Config for Test {
 	type Event = Event;
 	type WeightInfo = ();
+	type Currency = Balances;
+	type LinkDeposit = LinkDeposit;
+	type PendingDeposit = PendingDeposit;
+	type LinkDomain = account_linker::GenesisHashAndPalletIndex<Test>;
+	type EthChainId = EthChainId;
+	type BitcoinNetwork = BtcNetwork;
+	type LinkExclusivity = Exclusivity;
+	type MaxDisplacedAccounts = MaxDisplacedAccounts;
+	type PolkadotSignature = Signature;
+	type PolkadotSigner = sp_core::sr25519::Public;
+	type CosmosHrp = CosmosHrp;
+	type MaxEthLinks = MaxEthLinks;
+	type MaxBtcLinks = MaxBtcLinks;
+	type MaxTronLinks = MaxTronLinks;
+	type MaxSolanaLinks = MaxSolanaLinks;
+	type MaxCosmosLinks = MaxCosmosLinks;
+	type MaxPolkadotLinks = MaxPolkadotLinks;
+	type ExpiringBlockNumberMax = ExpiringBlockNumberMax;
+	type PendingSweepLimit = PendingSweepLimit;
+	type ContractLinkAttesters = ContractLinkAttesters;
+	type ContractLinkQuorum = ContractLinkQuorum;
+	type MaxContractSignatureLen = MaxContractSignatureLen;
+	type MaxLinkLabelLen = MaxLinkLabelLen;
+	type LinkValidityPeriod = LinkValidityPeriod;
+	type StaleSweepLimit = StaleSweepLimit;
+	type OnLinkChanged = ();
 }
 
 parameter_types! {
@@ -146,6 +206,8 @@ impl Config for Test {
 	type WeightInfo = ();
 	type MaximumWeightForDataAggregation = MaximumWeightForDataAggregation;
 	type MaximumCommitsPerSession = MaximumCommitsPerSession;
+	type LinkedAccounts = AccountLinker;
+	type ContractLinks = AccountLinker;
 }
 
 #[test]
@@ -250,6 +312,40 @@ fn test_parse_infura_balances_2() {
 	assert_eq!(token_info[0].id, 1);
 }
 
+#[test]
+fn test_eip1271_call_body() {
+	let body = urls::eip1271_call_body(&[0x11; 20], &[0x22; 32], &[0x33; 65]);
+	let expected = format!(
+		r#"{{"jsonrpc":"2.0","method":"eth_call","id":1,"params":[{{"to":"0x{}","data":"0x1626ba7e{}{}{}{}{}"}},"latest"]}}"#,
+		"11".repeat(20),
+		"22".repeat(32),
+		format!("{:064x}", 0x40),
+		format!("{:064x}", 65),
+		"33".repeat(65),
+		"00".repeat(31),
+	);
+	assert_eq!(String::from_utf8(body).unwrap(), expected);
+}
+
+#[test]
+fn test_parse_eip1271_response() {
+	let valid = r#"{"jsonrpc":"2.0","id":1,"result":"0x1626ba7e00000000000000000000000000000000000000000000000000000000"}"#;
+	assert_eq!(Some(true), urls::parse_eip1271_response(valid));
+
+	let invalid = r#"{"jsonrpc":"2.0","id":1,"result":"0xffffffff00000000000000000000000000000000000000000000000000000000"}"#;
+	assert_eq!(Some(false), urls::parse_eip1271_response(invalid));
+
+	// Not a contract
+	let empty = r#"{"jsonrpc":"2.0","id":1,"result":"0x"}"#;
+	assert_eq!(Some(false), urls::parse_eip1271_response(empty));
+
+	let reverted = r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted"}}"#;
+	assert_eq!(Some(false), urls::parse_eip1271_response(reverted));
+
+	let unavailable = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"daily request count exceeded"}}"#;
+	assert_eq!(None, urls::parse_eip1271_response(unavailable));
+}
+
 // fetch_balances only executed in offchain worker context, need investigate how to call it in test
 // #[test]
 // fn test_fetch_balances() {
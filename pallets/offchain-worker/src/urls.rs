@@ -330,6 +330,57 @@ pub fn parse_infura_balances(price_str: &str) -> Option<Vec<u128>> {
 	Some(result)
 }
 
+/// The selector of the EIP-1271 `isValidSignature(bytes32,bytes)` method, also the value it returns
+/// when the signature is valid
+pub const EIP1271_MAGIC_VALUE: &str = "1626ba7e";
+
+// Compose the `eth_call` JSON-RPC request calling `isValidSignature(hash, signature)` on `contract`
+pub fn eip1271_call_body(contract: &[u8; 20], hash: &[u8; 32], signature: &[u8]) -> Vec<u8> {
+	// The bytes argument goes after the two head words, padded to a multiple of 32 bytes
+	let mut data: Vec<u8> = Vec::new();
+	data.extend_from_slice(hash);
+	data.extend_from_slice(&abi_word(0x40));
+	data.extend_from_slice(&abi_word(signature.len() as u64));
+	data.extend_from_slice(signature);
+	data.resize(data.len() + (32 - signature.len() % 32) % 32, 0);
+
+	let mut body: Vec<u8> = Vec::new();
+	body.extend(r#"{"jsonrpc":"2.0","method":"eth_call","id":1,"params":[{"to":"0x"#.as_bytes());
+	body.extend(utils::address_to_string(contract));
+	body.extend(r#"","data":"0x"#.as_bytes());
+	body.extend(EIP1271_MAGIC_VALUE.as_bytes());
+	body.extend(utils::bytes_to_string(&data));
+	body.extend(r#""},"latest"]}"#.as_bytes());
+	body
+}
+
+// Big endian 32 bytes ABI encoding of an unsigned number
+fn abi_word(n: u64) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[24..].copy_from_slice(&n.to_be_bytes());
+	word
+}
+
+// Parse the `isValidSignature` result, None when the contract couldn't be queried
+pub fn parse_eip1271_response(resp_str: &str) -> Option<bool> {
+	// {"jsonrpc":"2.0","id":1,"result":"0x1626ba7e00000000000000000000000000000000000000000000000000000000"}
+	// {"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted"}}
+	let value: serde_json::Value = serde_json::from_str(resp_str).ok()?;
+
+	match (value["result"].as_str(), value["error"]["message"].as_str()) {
+		// The bytes4 return value is left aligned in a 32 bytes word
+		(Some(result), None) => Some(
+			result
+				.strip_prefix("0x")
+				.and_then(|word| word.get(..EIP1271_MAGIC_VALUE.len()))
+				.map_or(false, |value| value.eq_ignore_ascii_case(EIP1271_MAGIC_VALUE)),
+		),
+		// Contracts may revert instead of returning when the signature is invalid
+		(None, Some(message)) if message.starts_with("execution reverted") => Some(false),
+		_ => None,
+	}
+}
+
 // Parse the token from local server
 pub fn parse_store_tokens(resp_str: &str) -> Result<(), &'static str> {
     let token_info: Result<TokenInfo, _> = serde_json::from_str(&resp_str);
@@ -45,8 +45,13 @@ pub fn u8_to_str_byte(a: u8) -> u8 {
 
 // address to string bytes
 pub fn address_to_string(address: &[u8; 20]) -> Vec<u8> {
+	bytes_to_string(address)
+}
+
+// bytes to lowercase hex string bytes, without 0x prefix
+pub fn bytes_to_string(bytes: &[u8]) -> Vec<u8> {
 	let mut vec_result: Vec<u8> = Vec::new();
-	for item in address {
+	for item in bytes {
 		let a: u8 = item & 0x0F;
 		let b: u8 = item >> 4;
 		vec_result.push(u8_to_str_byte(b));
@@ -47,6 +47,7 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn asset_claim() -> Weight;
 	fn submit_balance() -> Weight;
+	fn submit_contract_link_attestation(d: u32, ) -> Weight;
 	fn on_finalize() -> Weight;
 }
 
@@ -63,6 +64,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	// placeholder, not generated by the benchmark CLI yet
+	fn submit_contract_link_attestation(d: u32, ) -> Weight {
+		(330_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(11 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
 	fn on_finalize() -> Weight {
 		(3_882_140_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(398 as Weight))
@@ -82,6 +91,14 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
+	// placeholder, not generated by the benchmark CLI yet
+	fn submit_contract_link_attestation(d: u32, ) -> Weight {
+		(330_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(11 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(d as Weight)))
+	}
 	fn on_finalize() -> Weight {
 		(3_882_140_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(398 as Weight))